@@ -0,0 +1,116 @@
+use std::fs::File;
+use std::io::{self, BufRead, Write};
+
+use input::{self, UNNAMED};
+use {AdventError, Result};
+
+/// The default location of the expected answers manifest.
+pub const DEFAULT_FILE: &str = "data/answers.txt";
+
+/// The known answer for one part of a puzzle when solved with the given input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub day: u32,
+    pub part: u32,
+    pub input: String,
+    pub answer: String,
+}
+
+/*
+File Format, tab separated with '\n', '\t' and '\\' escaped in the answer:
+# day	part	input	answer
+1	1	data/day1-input.txt	592
+*/
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Expected>> {
+    let mut answers = Vec::new();
+    for (i, line) in input::lines(reader).enumerate() {
+        let data = line?;
+        if data.is_empty() || data.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = data.splitn(4, '\t').collect();
+        if fields.len() != 4 {
+            return Err(AdventError::parse(UNNAMED, i + 1, 1, &data, "expected day, part, input and answer"));
+        }
+        let day = fields[0].parse::<u32>()
+            .map_err(|_| AdventError::parse(UNNAMED, i + 1, 1, fields[0], "not a day"))?;
+        let part = match fields[1] {
+            "1" => 1,
+            "2" => 2,
+            _ => return Err(AdventError::parse(UNNAMED, i + 1, fields[0].len() + 2, fields[1], "not a part")),
+        };
+        answers.push(Expected {
+            day,
+            part,
+            input: fields[2].to_string(),
+            answer: unescape(fields[3]),
+        });
+    }
+    Ok(answers)
+}
+
+pub fn read(filename: &str) -> Result<Vec<Expected>> {
+    input::with_file(filename, parse)
+}
+
+pub fn write<W: Write>(mut writer: W, answers: &[Expected]) -> io::Result<()> {
+    writeln!(writer, "# day\tpart\tinput\tanswer")?;
+    for a in answers {
+        writeln!(writer, "{}\t{}\t{}\t{}", a.day, a.part, a.input, escape(&a.answer))?;
+    }
+    Ok(())
+}
+
+pub fn write_file(filename: &str, answers: &[Expected]) -> Result<()> {
+    let file = File::create(filename).map_err(|e| AdventError::io(filename, e))?;
+    write(file, answers).map_err(|e| AdventError::io(filename, e))
+}
+
+/// Finds the expected answer for one part of a day.
+pub fn find(answers: &[Expected], day: u32, part: u32) -> Option<&Expected> {
+    answers.iter().find(|a| a.day == day && a.part == part)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n").replace('\t', "\\t")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_then_parse() {
+        let answers = vec![
+            Expected { day: 1, part: 1, input: "data/day1-input.txt".to_string(), answer: "592".to_string() },
+            Expected { day: 10, part: 1, input: "data/day10-input.txt".to_string(), answer: "#.\\\\\n.#\n".to_string() },
+        ];
+        let mut out = Vec::new();
+        write(&mut out, &answers).unwrap();
+        assert_eq!(answers, parse(out.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn parse_bad_part() {
+        let e = parse("# comment\n1\t3\tdata/day1-input.txt\t592".as_bytes()).unwrap_err();
+        assert_eq!("<input>:2:3: not a part: '3'", e.to_string());
+    }
+}
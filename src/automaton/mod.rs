@@ -0,0 +1,269 @@
+use simulation::Simulation;
+
+/// The next state of a cell for every neighbourhood of `2 * radius + 1` cells.
+///
+/// Cells take states `0..states`, a neighbourhood is read as a base `states` number with the
+/// leftmost cell most significant, so a two state radius 2 table has 2^5 entries. Every
+/// neighbourhood without a rule becomes state 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    radius: usize,
+    states: u8,
+    table: Vec<u8>,
+}
+
+impl Rules {
+    /// Panics when the table would not fit in memory, such as a radius of 20 with 3 states.
+    pub fn new(radius: usize, states: u8) -> Rules {
+        assert!(states >= 2, "an automaton needs at least two states");
+        let size = (states as usize)
+            .checked_pow(2 * radius as u32 + 1)
+            .filter(|&size| size <= 1 << 24)
+            .expect("rule table too large");
+        Rules {
+            radius,
+            states,
+            table: vec![0; size],
+        }
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// The number of cells in a neighbourhood.
+    pub fn width(&self) -> usize {
+        2 * self.radius + 1
+    }
+
+    /// Sets the state a cell takes when it is at the centre of `neighbourhood`.
+    pub fn set(&mut self, neighbourhood: &[u8], next: u8) {
+        assert!(next < self.states, "state {} is not below {}", next, self.states);
+        let i = self.index(neighbourhood);
+        self.table[i] = next;
+    }
+
+    pub fn get(&self, neighbourhood: &[u8]) -> u8 {
+        self.table[self.index(neighbourhood)]
+    }
+
+    fn index(&self, neighbourhood: &[u8]) -> usize {
+        assert_eq!(self.width(), neighbourhood.len(), "neighbourhood has the wrong width");
+        neighbourhood.iter().fold(0, |i, &cell| {
+            assert!(cell < self.states, "state {} is not below {}", cell, self.states);
+            i * self.states as usize + cell as usize
+        })
+    }
+}
+
+/// A one dimensional cellular automaton on an infinite tape.
+///
+/// Only the cells between the first and last cell that differ from the background are stored,
+/// every other cell is in the background state. The background starts as state 0 and follows
+/// the rule for a neighbourhood made only of background cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Automaton {
+    rules: Rules,
+    cells: Vec<u8>,
+    left: i64,
+    background: u8,
+    generation: u64,
+}
+
+impl Automaton {
+    /// Starts an automaton with `initial` at positions `0..initial.len()`.
+    pub fn new(rules: Rules, initial: &[u8]) -> Automaton {
+        for &cell in initial {
+            assert!(cell < rules.states, "state {} is not below {}", cell, rules.states);
+        }
+        let mut automaton = Automaton {
+            rules,
+            cells: initial.to_vec(),
+            left: 0,
+            background: 0,
+            generation: 0,
+        };
+        automaton.trim();
+        automaton
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// The number of steps taken so far.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The state of every cell outside `cells`.
+    pub fn background(&self) -> u8 {
+        self.background
+    }
+
+    /// The position of the first cell that differs from the background.
+    pub fn left(&self) -> i64 {
+        self.left
+    }
+
+    /// The cells from `left` up to the last cell that differs from the background.
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn get(&self, position: i64) -> u8 {
+        let i = position - self.left;
+        if i < 0 || i >= self.cells.len() as i64 {
+            self.background
+        } else {
+            self.cells[i as usize]
+        }
+    }
+
+    /// Every stored cell with its position.
+    pub fn iter(&self) -> impl Iterator<Item=(i64, u8)> + '_ {
+        let left = self.left;
+        self.cells.iter().enumerate().map(move |(i, &cell)| (left + i as i64, cell))
+    }
+
+    /// Advances every cell by one generation.
+    pub fn step(&mut self) {
+        let radius = self.rules.radius as i64;
+        let states = self.rules.states as usize;
+        let size = self.rules.table.len();
+
+        // the neighbourhood is rolled along the tape one cell at a time, starting with the
+        // background to the left of the first cell that can change
+        let first = self.left - radius;
+        let last = self.left + self.cells.len() as i64 + radius;
+        let mut neighbourhood = 0;
+        for position in first - radius..first + radius {
+            neighbourhood = (neighbourhood * states + self.get(position) as usize) % size;
+        }
+        let mut next = Vec::with_capacity(self.cells.len() + 2 * radius as usize);
+        for position in first..last {
+            neighbourhood = (neighbourhood * states + self.get(position + radius) as usize) % size;
+            next.push(self.rules.table[neighbourhood]);
+        }
+
+        let background = vec![self.background; self.rules.width()];
+        self.background = self.rules.get(&background);
+        self.cells = next;
+        self.left = first;
+        self.generation += 1;
+        self.trim();
+    }
+
+    pub fn steps(&mut self, count: u64) {
+        for _ in 0..count {
+            self.step();
+        }
+    }
+
+    fn trim(&mut self) {
+        let background = self.background;
+        let end = self.cells.iter().rposition(|&c| c != background).map_or(0, |i| i + 1);
+        self.cells.truncate(end);
+        let start = self.cells.iter().position(|&c| c != background).unwrap_or(0);
+        self.cells.drain(..start);
+        self.left += start as i64;
+    }
+}
+
+impl Simulation for Automaton {
+    /// The position of the first stored cell and the stored cells.
+    type State = (i64, Vec<u8>);
+
+    fn step(&mut self) {
+        Automaton::step(self);
+    }
+
+    fn snapshot(&self) -> (i64, Vec<u8>) {
+        (self.left, self.cells.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wolfram's elementary automaton `number`, a radius 1 two state automaton.
+    fn elementary(number: u8) -> Rules {
+        let mut rules = Rules::new(1, 2);
+        for i in 0..8u8 {
+            rules.set(&[i >> 2 & 1, i >> 1 & 1, i & 1], number >> i & 1);
+        }
+        rules
+    }
+
+    fn show(automaton: &Automaton) -> String {
+        automaton.cells().iter().map(|&c| if c == 1 { '#' } else { '.' }).collect()
+    }
+
+    #[test]
+    fn rule_table() {
+        let rules = elementary(90);
+        assert_eq!(8, rules.table.len());
+        assert_eq!(1, rules.get(&[1, 0, 0]));
+        assert_eq!(0, rules.get(&[1, 0, 1]));
+        assert_eq!(32, Rules::new(2, 2).table.len());
+        assert_eq!(27, Rules::new(1, 3).table.len());
+    }
+
+    #[test]
+    fn grows_in_both_directions() {
+        // rule 90 draws a Sierpinski triangle
+        let mut automaton = Automaton::new(elementary(90), &[0, 0, 1, 0]);
+        assert_eq!((2, "#".to_string()), (automaton.left(), show(&automaton)));
+        automaton.step();
+        assert_eq!((1, "#.#".to_string()), (automaton.left(), show(&automaton)));
+        automaton.steps(2);
+        assert_eq!((-1, "#.#.#.#".to_string()), (automaton.left(), show(&automaton)));
+        assert_eq!(3, automaton.generation());
+        assert_eq!(1, automaton.get(-1));
+        assert_eq!(0, automaton.get(-2));
+    }
+
+    #[test]
+    fn trims_and_moves() {
+        // rule 2 moves a single cell one place left each generation
+        let mut automaton = Automaton::new(elementary(2), &[1]);
+        automaton.steps(5);
+        assert_eq!(vec![(-5, 1)], automaton.iter().collect::<Vec<_>>());
+
+        // everything dies under rule 0
+        let mut automaton = Automaton::new(elementary(0), &[1, 1, 0, 1]);
+        automaton.step();
+        assert!(automaton.cells().is_empty());
+    }
+
+    #[test]
+    fn background_follows_the_rules() {
+        // rule 1 turns an empty neighbourhood full and a full one empty
+        let mut automaton = Automaton::new(elementary(1), &[]);
+        automaton.step();
+        assert_eq!((1, 0), (automaton.background(), automaton.cells().len()));
+        automaton.step();
+        assert_eq!(0, automaton.background());
+    }
+
+    #[test]
+    fn multiple_states_and_radius() {
+        // each cell becomes the sum of its radius 2 neighbourhood modulo 3
+        let mut rules = Rules::new(2, 3);
+        for i in 0..243usize {
+            let cells: Vec<u8> = (0..5).rev().map(|d| (i / 3usize.pow(d) % 3) as u8).collect();
+            let sum = cells.iter().map(|&c| c as usize).sum::<usize>();
+            rules.set(&cells, (sum % 3) as u8);
+        }
+        let mut automaton = Automaton::new(rules, &[2]);
+        automaton.step();
+        assert_eq!((-2, &[2, 2, 2, 2, 2][..]), (automaton.left(), automaton.cells()));
+        automaton.step();
+        assert_eq!((-4, &[2, 1, 0, 2, 1, 2, 0, 1, 2][..]), (automaton.left(), automaton.cells()));
+    }
+}
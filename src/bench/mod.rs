@@ -0,0 +1,209 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use input::{self, UNNAMED};
+use {AdventError, Result};
+
+/// The default file benchmark results are written to.
+pub const DEFAULT_OUTPUT: &str = "target/advent-bench.txt";
+
+/// Summary statistics of the sampled run times of one part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "at least one sample is required");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let count = sorted.len();
+        let median = if count.is_multiple_of(2) {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2
+        } else {
+            sorted[count / 2]
+        };
+
+        let nanos: Vec<f64> = sorted.iter().map(|d| d.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / count as f64;
+        let variance = nanos.iter().map(|n| (n - mean) * (n - mean)).sum::<f64>() / count as f64;
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean as u64),
+            stddev: Duration::from_nanos(variance.sqrt() as u64),
+        }
+    }
+}
+
+/// The benchmark statistics for one part of a day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub day: u32,
+    pub part: u32,
+    pub stats: Stats,
+}
+
+/// A part whose median run time increased by more than the allowed threshold.
+#[derive(Debug, PartialEq)]
+pub struct Regression {
+    pub day: u32,
+    pub part: u32,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// Increase of the median as a percentage of the baseline median.
+    pub increase: f64,
+}
+
+/// Calls `f` `warmup` times without timing it, then times `iterations` calls.
+pub fn measure<T, F>(warmup: usize, iterations: usize, mut f: F) -> Stats
+    where F: FnMut() -> T {
+    for _ in 0..warmup {
+        f();
+    }
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        f();
+        samples.push(start.elapsed());
+    }
+    Stats::from_samples(&samples)
+}
+
+/// Flags every part measured in both runs whose median slowed down by more than `threshold` percent.
+pub fn compare(baseline: &[Measurement], current: &[Measurement], threshold: f64) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+    for c in current {
+        let b = match baseline.iter().find(|b| b.day == c.day && b.part == c.part) {
+            None => continue,
+            Some(b) => b,
+        };
+        let baseline_nanos = b.stats.median.as_nanos() as f64;
+        let current_nanos = c.stats.median.as_nanos() as f64;
+        if baseline_nanos == 0.0 {
+            continue;
+        }
+        let increase = (current_nanos - baseline_nanos) / baseline_nanos * 100.0;
+        if increase > threshold {
+            regressions.push(Regression {
+                day: c.day,
+                part: c.part,
+                baseline: b.stats.median,
+                current: c.stats.median,
+                increase,
+            });
+        }
+    }
+    regressions
+}
+
+/*
+File Format, tab separated with every time in nanoseconds:
+# day	part	min	median	mean	stddev
+1	1	97344	101203	103954	4822
+*/
+pub fn parse<R: BufRead>(reader: R) -> Result<Vec<Measurement>> {
+    let mut measurements = Vec::new();
+    for (i, line) in input::lines(reader).enumerate() {
+        let data = line?;
+        if data.is_empty() || data.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = data.split('\t').collect();
+        if fields.len() != 6 {
+            return Err(AdventError::parse(UNNAMED, i + 1, 1, &data, "expected day, part and four times"));
+        }
+        let mut values = [0u64; 6];
+        let mut column = 1;
+        for (value, field) in values.iter_mut().zip(&fields) {
+            *value = field.parse::<u64>()
+                .map_err(|_| AdventError::parse(UNNAMED, i + 1, column, field, "not an int"))?;
+            column += field.len() + 1;
+        }
+        measurements.push(Measurement {
+            day: values[0] as u32,
+            part: values[1] as u32,
+            stats: Stats {
+                min: Duration::from_nanos(values[2]),
+                median: Duration::from_nanos(values[3]),
+                mean: Duration::from_nanos(values[4]),
+                stddev: Duration::from_nanos(values[5]),
+            },
+        });
+    }
+    Ok(measurements)
+}
+
+pub fn read(filename: &str) -> Result<Vec<Measurement>> {
+    input::with_file(filename, parse)
+}
+
+pub fn write<W: Write>(mut writer: W, measurements: &[Measurement]) -> io::Result<()> {
+    writeln!(writer, "# day\tpart\tmin\tmedian\tmean\tstddev")?;
+    for m in measurements {
+        writeln!(writer, "{}\t{}\t{}\t{}\t{}\t{}", m.day, m.part, m.stats.min.as_nanos(),
+                 m.stats.median.as_nanos(), m.stats.mean.as_nanos(), m.stats.stddev.as_nanos())?;
+    }
+    Ok(())
+}
+
+pub fn write_file(filename: &str, measurements: &[Measurement]) -> Result<()> {
+    if let Some(parent) = Path::new(filename).parent() {
+        fs::create_dir_all(parent).map_err(|e| AdventError::io(filename, e))?;
+    }
+    let file = File::create(filename).map_err(|e| AdventError::io(filename, e))?;
+    write(file, measurements).map_err(|e| AdventError::io(filename, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn measurement(day: u32, part: u32, median: u64) -> Measurement {
+        Measurement {
+            day,
+            part,
+            stats: Stats { min: ms(median), median: ms(median), mean: ms(median), stddev: ms(0) },
+        }
+    }
+
+    #[test]
+    fn stats_from_samples() {
+        let stats = Stats::from_samples(&[ms(4), ms(2), ms(8), ms(6)]);
+        assert_eq!(ms(2), stats.min);
+        assert_eq!(ms(5), stats.median);
+        assert_eq!(ms(5), stats.mean);
+        assert_eq!(Duration::from_nanos(2_236_067), stats.stddev);
+    }
+
+    #[test]
+    fn compare_flags_regressions() {
+        let baseline = vec![measurement(1, 1, 100), measurement(1, 2, 100)];
+        let current = vec![measurement(1, 1, 105), measurement(1, 2, 150), measurement(2, 1, 500)];
+        let regressions = compare(&baseline, &current, 10.0);
+        assert_eq!(1, regressions.len());
+        assert_eq!((1, 2), (regressions[0].day, regressions[0].part));
+        assert_eq!(50.0, regressions[0].increase);
+    }
+
+    #[test]
+    fn write_then_parse() {
+        let measurements = vec![measurement(1, 1, 100), measurement(12, 2, 3)];
+        let mut out = Vec::new();
+        write(&mut out, &measurements).unwrap();
+        assert_eq!(measurements, parse(out.as_slice()).unwrap());
+    }
+}
@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use {AdventError, Result};
+
+/// A cooperative cancellation token, clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct Token {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Token {
+    pub fn new() -> Token {
+        Token::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `token` as the current thread's token, restoring the previous token afterwards.
+pub fn with_token<T, F>(token: Token, f: F) -> T
+    where F: FnOnce() -> T {
+    let previous = CURRENT.with(|current| current.replace(Some(token)));
+    let result = f();
+    CURRENT.with(|current| current.replace(previous));
+    result
+}
+
+/// Checks the current thread's token, long running loops call this on every iteration so the
+/// runner can stop them. Without a token nothing is ever cancelled.
+pub fn check() -> Result<()> {
+    CURRENT.with(|current| match *current.borrow() {
+        Some(ref token) if token.is_cancelled() => Err(AdventError::Cancelled),
+        _ => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_token() {
+        assert!(check().is_ok());
+
+        let token = Token::new();
+        let cancelled = with_token(token.clone(), || {
+            assert!(check().is_ok());
+            token.cancel();
+            check()
+        });
+        assert_eq!("cancelled", cancelled.unwrap_err().to_string());
+
+        // the token only applies inside with_token
+        assert!(check().is_ok());
+    }
+}
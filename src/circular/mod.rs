@@ -0,0 +1,318 @@
+use std::iter::FromIterator;
+
+/// Marks a node that holds no value and sits on the free list.
+const FREE: usize = usize::MAX;
+
+struct Node<T> {
+    value: Option<T>,
+    next: usize,
+    previous: usize,
+}
+
+/// A ring of values with a cursor, stored as a doubly linked list inside a `Vec`.
+///
+/// Inserting and removing next to the cursor is O(1), removed nodes are kept on a free list and
+/// reused so a long running game does not allocate once the ring has reached its largest size.
+pub struct CircularList<T> {
+    nodes: Vec<Node<T>>,
+    free: Vec<usize>,
+    cursor: usize,
+    len: usize,
+}
+
+impl<T> CircularList<T> {
+    pub fn new() -> CircularList<T> {
+        CircularList::with_capacity(0)
+    }
+
+    /// Creates an empty list with room for `capacity` values before it reallocates.
+    pub fn with_capacity(capacity: usize) -> CircularList<T> {
+        CircularList {
+            nodes: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            cursor: FREE,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The value under the cursor.
+    pub fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.nodes[self.cursor].value.as_ref()
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.nodes[self.cursor].value.as_mut()
+    }
+
+    /// Moves the cursor `offset` values forwards, or backwards when `offset` is negative. The
+    /// cursor takes the shorter way round so moving by more than the length is cheap.
+    pub fn move_by(&mut self, offset: isize) {
+        if self.is_empty() {
+            return;
+        }
+        let len = self.len as isize;
+        let forward = offset.rem_euclid(len);
+        if forward <= len / 2 {
+            for _ in 0..forward {
+                self.cursor = self.nodes[self.cursor].next;
+            }
+        } else {
+            for _ in forward..len {
+                self.cursor = self.nodes[self.cursor].previous;
+            }
+        }
+    }
+
+    /// Inserts `value` after the cursor and moves the cursor onto it.
+    pub fn insert_after(&mut self, value: T) {
+        if self.is_empty() {
+            self.insert_first(value);
+            return;
+        }
+        let previous = self.cursor;
+        let next = self.nodes[previous].next;
+        self.cursor = self.link(value, previous, next);
+    }
+
+    /// Inserts `value` before the cursor and moves the cursor onto it.
+    pub fn insert_before(&mut self, value: T) {
+        if self.is_empty() {
+            self.insert_first(value);
+            return;
+        }
+        let next = self.cursor;
+        let previous = self.nodes[next].previous;
+        self.cursor = self.link(value, previous, next);
+    }
+
+    /// Removes the value under the cursor, the cursor moves on to the value that followed it.
+    pub fn remove(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let removed = self.cursor;
+        let (previous, next) = (self.nodes[removed].previous, self.nodes[removed].next);
+        self.nodes[previous].next = next;
+        self.nodes[next].previous = previous;
+        self.nodes[removed].next = FREE;
+        self.nodes[removed].previous = FREE;
+        self.free.push(removed);
+        self.len -= 1;
+        self.cursor = if self.is_empty() { FREE } else { next };
+        self.nodes[removed].value.take()
+    }
+
+    /// Every value once, starting at the cursor and moving forwards.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            node: self.cursor,
+            remaining: self.len,
+        }
+    }
+
+    fn insert_first(&mut self, value: T) {
+        let node = self.allocate(value);
+        self.nodes[node].next = node;
+        self.nodes[node].previous = node;
+        self.cursor = node;
+        self.len = 1;
+    }
+
+    /// Places `value` between two neighbouring nodes and returns its node.
+    fn link(&mut self, value: T, previous: usize, next: usize) -> usize {
+        let node = self.allocate(value);
+        self.nodes[node].previous = previous;
+        self.nodes[node].next = next;
+        self.nodes[previous].next = node;
+        self.nodes[next].previous = node;
+        self.len += 1;
+        node
+    }
+
+    fn allocate(&mut self, value: T) -> usize {
+        match self.free.pop() {
+            Some(i) => {
+                self.nodes[i].value = Some(value);
+                i
+            }
+            None => {
+                self.nodes.push(Node { value: Some(value), next: FREE, previous: FREE });
+                self.nodes.len() - 1
+            }
+        }
+    }
+}
+
+impl<T> Default for CircularList<T> {
+    fn default() -> CircularList<T> {
+        CircularList::new()
+    }
+}
+
+/// Builds a list in iteration order with the cursor on the first value.
+impl<T> FromIterator<T> for CircularList<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> CircularList<T> {
+        let iter = iter.into_iter();
+        let mut list = CircularList::with_capacity(iter.size_hint().0);
+        for value in iter {
+            list.insert_after(value);
+        }
+        list.move_by(1);
+        list
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CircularList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T: 'a> {
+    list: &'a CircularList<T>,
+    node: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = &self.list.nodes[self.node];
+        self.node = node.next;
+        self.remaining -= 1;
+        node.value.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// A small xorshift generator so the property tests are repeatable without extra crates.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    #[test]
+    fn cursor() {
+        let mut list: CircularList<i32> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(3, list.len());
+        assert_eq!(Some(&1), list.peek());
+        list.move_by(-1);
+        assert_eq!(Some(&3), list.peek());
+        list.move_by(5);
+        assert_eq!(Some(&2), list.peek());
+
+        list.insert_after(10);
+        assert_eq!(vec![10, 3, 1, 2], list.iter().cloned().collect::<Vec<_>>());
+        list.insert_before(20);
+        assert_eq!(vec![20, 10, 3, 1, 2], list.iter().cloned().collect::<Vec<_>>());
+
+        *list.peek_mut().unwrap() += 1;
+        assert_eq!(Some(21), list.remove());
+        assert_eq!(Some(&10), list.peek());
+        assert_eq!(4, list.len());
+    }
+
+    #[test]
+    fn empty() {
+        let mut list: CircularList<&str> = CircularList::new();
+        assert!(list.is_empty());
+        assert_eq!(None, list.peek());
+        assert_eq!(None, list.remove());
+        list.move_by(3);
+        assert_eq!(0, list.iter().count());
+
+        list.insert_before("a");
+        assert_eq!(Some("a"), list.remove());
+        assert!(list.is_empty());
+        list.insert_after("b");
+        assert_eq!(vec!["b"], list.iter().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn reuses_removed_nodes() {
+        let mut list: CircularList<usize> = (0..4).collect();
+        list.remove();
+        list.remove();
+        list.insert_after(7);
+        list.insert_after(8);
+        assert_eq!(4, list.nodes.len());
+        assert_eq!(vec![8, 3, 2, 7], list.iter().cloned().collect::<Vec<_>>());
+    }
+
+    /// Compares random operations against a `VecDeque` whose front is the cursor.
+    #[test]
+    fn matches_vec_deque_rotation() {
+        let mut random = Random(0x2018_1209);
+        for _ in 0..200 {
+            let mut list = CircularList::new();
+            let mut model: VecDeque<u64> = VecDeque::new();
+            for value in 0..100 {
+                match random.next(4) {
+                    0 => {
+                        let offset = random.next(41) as isize - 20;
+                        list.move_by(offset);
+                        if !model.is_empty() {
+                            let len = model.len() as isize;
+                            model.rotate_left(offset.rem_euclid(len) as usize);
+                        }
+                    }
+                    1 => {
+                        list.insert_after(value);
+                        if model.is_empty() {
+                            model.push_back(value);
+                        } else {
+                            model.insert(1, value);
+                            model.rotate_left(1);
+                        }
+                    }
+                    2 => {
+                        list.insert_before(value);
+                        model.push_front(value);
+                    }
+                    _ => {
+                        assert_eq!(model.pop_front(), list.remove());
+                    }
+                }
+                assert_eq!(model.len(), list.len());
+                assert_eq!(model.front(), list.peek());
+                assert!(model.iter().eq(list.iter()));
+            }
+        }
+    }
+}
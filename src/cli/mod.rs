@@ -0,0 +1,682 @@
+use std::time::Duration;
+
+use answers;
+use bench;
+use input;
+use profile;
+use render::Encoding;
+use report::Format;
+use watch;
+
+/// How long a command lets a part take before reporting a timeout.
+pub const DEFAULT_TIMEOUT: u64 = 60;
+
+pub const USAGE: &str = "usage: advent <command> [options]
+
+commands:
+    run <day> [--part <1|2>] [--input <file>]    run a single day
+    run --all [--part <1|2>]                     run every registered day
+        [--format <text|json|csv|markdown>]      how results are printed (default text)
+        [--parallel]                             solve the parts concurrently
+        [--memory]                               count the allocations made by each part
+        [--profile <name>]                       read the inputs in data/<name>/ instead of data/
+        [--timeout <seconds>]                    give up on a part after this long (default 60,
+                                                 0 waits forever)
+    list                                         list the registered days
+    verify [--answers <file>] [--record]         check every day against the expected answers,
+           [--profile <name>]                    --record saves the current answers instead
+    matrix [--part <1|2>] [--profile <name>]...  check every day against every profile's inputs and
+                                                 answers (default: data/ and each directory in it)
+    bench <day>|--all [--part <1|2>]             time every part repeatedly
+          [--iterations <n>] [--warmup <n>]      (default 10 timed runs after 2 warm-up runs)
+          [--output <file>]                      write the results (default target/advent-bench.txt)
+          [--baseline <file>] [--threshold <%>]  flag parts whose median slowed down by more than
+                                                 the threshold (default 10%) since the baseline
+    watch <day> [--part <1|2>] [--input <file>]  re-run a day whenever its input or example file
+          [--interval <ms>]                      changes (checked every 500ms by default)
+    lint <day> [<file>]                          check an input against the day's grammar without
+                                                 solving it (default: the day's own input)
+    render <day> [--input <file>]                draw the day's puzzle as a Netpbm image, binary
+           [--output <file>] [--plain]           unless --plain (default target/day<day>.<ext>)
+           [--ascii]                             print the picture as text instead
+    new <day> [--title <title>]                  generate and register a module for a new day
+    help                                         show this message
+
+Running advent without a command runs every registered day. An input file of '-' reads stdin.
+verify, matrix, bench, watch and render take the same --timeout as run. Every time reported is
+wall time, not CPU time, so with --parallel the sum of the part times can exceed the run's total.
+A missing puzzle input is downloaded from $ADVENT_BASE_URL/day/<day>/input with the session token
+in $ADVENT_SESSION when both are set, and kept in data/ so it is only downloaded once.";
+
+/// Which days a command applies to.
+#[derive(Debug, PartialEq)]
+pub enum Days {
+    All,
+    Day(u32),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+    pub days: Days,
+    pub part: Option<u32>,
+    pub input: Option<String>,
+    pub format: Format,
+    pub parallel: bool,
+    pub memory: bool,
+    pub profile: Option<String>,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyOptions {
+    pub answers: String,
+    pub record: bool,
+    pub profile: Option<String>,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MatrixOptions {
+    pub part: Option<u32>,
+    /// Every profile is checked when none are given.
+    pub profiles: Vec<String>,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    pub days: Days,
+    pub part: Option<u32>,
+    pub iterations: usize,
+    pub warmup: usize,
+    pub output: String,
+    pub baseline: Option<String>,
+    pub threshold: f64,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WatchOptions {
+    pub day: u32,
+    pub part: Option<u32>,
+    pub input: Option<String>,
+    pub interval: u64,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LintOptions {
+    pub day: u32,
+    pub input: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RenderOptions {
+    pub day: u32,
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub encoding: Encoding,
+    pub ascii: bool,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NewOptions {
+    pub day: u32,
+    pub title: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    List,
+    Verify(VerifyOptions),
+    Matrix(MatrixOptions),
+    Bench(BenchOptions),
+    Watch(WatchOptions),
+    Lint(LintOptions),
+    Render(RenderOptions),
+    New(NewOptions),
+    Help,
+}
+
+/// Parses the command line arguments, not including the program name.
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter();
+    let command = match args.next() {
+        None => {
+            return Ok(Command::Run(RunOptions {
+                days: Days::All,
+                part: None,
+                input: None,
+                format: Format::Text,
+                parallel: false,
+                memory: false,
+                profile: None,
+                timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+            }));
+        }
+        Some(c) => c,
+    };
+
+    match command.as_str() {
+        "run" => parse_run(&mut args),
+        "list" => no_arguments(Command::List, &mut args),
+        "verify" => parse_verify(&mut args),
+        "matrix" => parse_matrix(&mut args),
+        "bench" => parse_bench(&mut args),
+        "watch" => parse_watch(&mut args),
+        "lint" => parse_lint(&mut args),
+        "render" => parse_render(&mut args),
+        "new" => parse_new(&mut args),
+        "help" | "--help" | "-h" => no_arguments(Command::Help, &mut args),
+        _ => Err(format!("unknown command '{}'", command)),
+    }
+}
+
+fn no_arguments<'a, I>(command: Command, args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    match args.next() {
+        None => Ok(command),
+        Some(arg) => Err(format!("unexpected argument '{}'", arg)),
+    }
+}
+
+fn parse_run<'a, I>(args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    let mut all = false;
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut format = Format::Text;
+    let mut parallel = false;
+    let mut memory = false;
+    let mut profile = None;
+    let mut timeout = Some(Duration::from_secs(DEFAULT_TIMEOUT));
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" | "-a" => all = true,
+            "--part" | "-p" => part = Some(parse_part(flag_value(arg, args)?)?),
+            "--input" | "-i" => input = Some(flag_value(arg, args)?.clone()),
+            "--parallel" => parallel = true,
+            "--memory" => memory = true,
+            "--profile" => profile = Some(parse_profile(flag_value(arg, args)?)?),
+            "--timeout" => timeout = parse_timeout(arg, flag_value(arg, args)?)?,
+            "--format" | "-f" => {
+                let value = flag_value(arg, args)?;
+                format = Format::from_name(value).ok_or_else(|| format!("unknown format '{}'", value))?;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => {
+                if day.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                day = Some(parse_day(arg)?);
+            }
+        }
+    }
+
+    let days = select_days(all, day)?;
+    if days == Days::All && input.is_some() {
+        return Err("--input requires a single day".to_string());
+    }
+    if input.is_some() && profile.is_some() {
+        return Err("--input cannot be combined with --profile".to_string());
+    }
+    // the allocation counters are shared by every thread
+    if parallel && memory {
+        return Err("--memory cannot be combined with --parallel".to_string());
+    }
+
+    Ok(Command::Run(RunOptions { days, part, input, format, parallel, memory, profile, timeout }))
+}
+
+fn parse_verify<'a, I>(args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    let mut answers = None;
+    let mut record = false;
+    let mut profile = None;
+    let mut timeout = Some(Duration::from_secs(DEFAULT_TIMEOUT));
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => answers = Some(flag_value(arg, args)?.clone()),
+            "--record" => record = true,
+            "--profile" => profile = Some(parse_profile(flag_value(arg, args)?)?),
+            "--timeout" => timeout = parse_timeout(arg, flag_value(arg, args)?)?,
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    // each profile keeps its own answers
+    let answers = answers.unwrap_or_else(|| match profile {
+        Some(ref p) => profile::answers_filename(p),
+        None => answers::DEFAULT_FILE.to_string(),
+    });
+    Ok(Command::Verify(VerifyOptions { answers, record, profile, timeout }))
+}
+
+fn parse_matrix<'a, I>(args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    let mut options = MatrixOptions {
+        part: None,
+        profiles: Vec::new(),
+        timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => options.part = Some(parse_part(flag_value(arg, args)?)?),
+            "--profile" => options.profiles.push(parse_profile(flag_value(arg, args)?)?),
+            "--timeout" => options.timeout = parse_timeout(arg, flag_value(arg, args)?)?,
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Matrix(options))
+}
+
+fn parse_profile(value: &str) -> Result<String, String> {
+    profile::validate(value).map(|_| value.to_string())
+}
+
+fn parse_bench<'a, I>(args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    let mut all = false;
+    let mut day = None;
+    let mut options = BenchOptions {
+        days: Days::All,
+        part: None,
+        iterations: 10,
+        warmup: 2,
+        output: bench::DEFAULT_OUTPUT.to_string(),
+        baseline: None,
+        threshold: 10.0,
+        timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" | "-a" => all = true,
+            "--part" | "-p" => options.part = Some(parse_part(flag_value(arg, args)?)?),
+            "--iterations" | "-n" => options.iterations = parse_count(arg, flag_value(arg, args)?, 1)?,
+            "--warmup" => options.warmup = parse_count(arg, flag_value(arg, args)?, 0)?,
+            "--output" | "-o" => options.output = flag_value(arg, args)?.clone(),
+            "--baseline" => options.baseline = Some(flag_value(arg, args)?.clone()),
+            "--timeout" => options.timeout = parse_timeout(arg, flag_value(arg, args)?)?,
+            "--threshold" => {
+                let value = flag_value(arg, args)?;
+                options.threshold = match value.trim_end_matches('%').parse::<f64>() {
+                    Ok(t) if t >= 0.0 => t,
+                    _ => return Err(format!("--threshold must be a percentage, got '{}'", value)),
+                };
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => {
+                if day.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                day = Some(parse_day(arg)?);
+            }
+        }
+    }
+
+    options.days = select_days(all, day)?;
+    Ok(Command::Bench(options))
+}
+
+fn parse_watch<'a, I>(args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut interval = watch::DEFAULT_INTERVAL;
+    let mut timeout = Some(Duration::from_secs(DEFAULT_TIMEOUT));
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => part = Some(parse_part(flag_value(arg, args)?)?),
+            "--input" | "-i" => input = Some(flag_value(arg, args)?.clone()),
+            "--interval" => interval = parse_count(arg, flag_value(arg, args)?, 1)? as u64,
+            "--timeout" => timeout = parse_timeout(arg, flag_value(arg, args)?)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => {
+                if day.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                day = Some(parse_day(arg)?);
+            }
+        }
+    }
+
+    let day = day.ok_or_else(|| "expected a day".to_string())?;
+    if input.as_ref().is_some_and(|i| i == input::STDIN) {
+        return Err("watch cannot read stdin".to_string());
+    }
+    Ok(Command::Watch(WatchOptions { day, part, input, interval, timeout }))
+}
+
+fn parse_lint<'a, I>(args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    let day = match args.next() {
+        None => return Err("expected a day".to_string()),
+        Some(arg) => parse_day(arg)?,
+    };
+    let input = args.next().cloned();
+    no_arguments(Command::Lint(LintOptions { day, input }), args)
+}
+
+fn parse_render<'a, I>(args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    let mut day = None;
+    let mut input = None;
+    let mut output = None;
+    let mut encoding = Encoding::Binary;
+    let mut ascii = false;
+    let mut timeout = Some(Duration::from_secs(DEFAULT_TIMEOUT));
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = Some(flag_value(arg, args)?.clone()),
+            "--output" | "-o" => output = Some(flag_value(arg, args)?.clone()),
+            "--plain" => encoding = Encoding::Plain,
+            "--ascii" => ascii = true,
+            "--timeout" => timeout = parse_timeout(arg, flag_value(arg, args)?)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => {
+                if day.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                day = Some(parse_day(arg)?);
+            }
+        }
+    }
+
+    let day = day.ok_or_else(|| "expected a day".to_string())?;
+    if ascii && (output.is_some() || encoding == Encoding::Plain) {
+        return Err("--ascii cannot be combined with --output or --plain".to_string());
+    }
+    Ok(Command::Render(RenderOptions { day, input, output, encoding, ascii, timeout }))
+}
+
+fn parse_new<'a, I>(args: &mut I) -> Result<Command, String>
+    where I: Iterator<Item=&'a String> {
+    let mut day = None;
+    let mut title = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--title" | "-t" => title = Some(flag_value(arg, args)?.clone()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => {
+                if day.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                day = Some(parse_day(arg)?);
+            }
+        }
+    }
+
+    let day = day.ok_or_else(|| "expected a day".to_string())?;
+    let title = title.unwrap_or_else(|| format!("Day {}", day));
+    Ok(Command::New(NewOptions { day, title }))
+}
+
+fn select_days(all: bool, day: Option<u32>) -> Result<Days, String> {
+    match (all, day) {
+        (true, None) => Ok(Days::All),
+        (false, Some(d)) => Ok(Days::Day(d)),
+        (true, Some(_)) => Err("--all cannot be combined with a day".to_string()),
+        (false, None) => Err("expected a day or --all".to_string()),
+    }
+}
+
+/// A number of seconds, 0 means no timeout at all.
+fn parse_timeout(flag: &str, value: &str) -> Result<Option<Duration>, String> {
+    let seconds = parse_count(flag, value, 0)? as u64;
+    Ok(if seconds == 0 { None } else { Some(Duration::from_secs(seconds)) })
+}
+
+fn parse_part(value: &str) -> Result<u32, String> {
+    match value {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("part must be 1 or 2, got '{}'", value)),
+    }
+}
+
+fn parse_count(flag: &str, value: &str, min: usize) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(format!("{} must be a number of at least {}, got '{}'", flag, min, value)),
+    }
+}
+
+fn flag_value<'a, I>(flag: &str, args: &mut I) -> Result<&'a String, String>
+    where I: Iterator<Item=&'a String> {
+    args.next().ok_or_else(|| format!("{} requires a value", flag))
+}
+
+fn parse_day(arg: &str) -> Result<u32, String> {
+    match arg.parse::<u32>() {
+        Ok(d) if (1..=25).contains(&d) => Ok(d),
+        _ => Err(format!("day must be between 1 and 25, got '{}'", arg)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_no_arguments() {
+        let expected = Command::Run(RunOptions {
+            days: Days::All,
+            part: None,
+            input: None,
+            format: Format::Text,
+            parallel: false,
+            memory: false,
+            profile: None,
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+        });
+        assert_eq!(Ok(expected), parse(&args("")));
+    }
+
+    #[test]
+    fn parse_run_day() {
+        let expected = Command::Run(RunOptions {
+            days: Days::Day(7),
+            part: Some(2),
+            input: Some("other.txt".to_string()),
+            format: Format::Text,
+            parallel: false,
+            memory: true,
+            profile: None,
+            timeout: Some(Duration::from_secs(5)),
+        });
+        assert_eq!(Ok(expected), parse(&args("run 7 --part 2 --input other.txt --memory --timeout 5")));
+    }
+
+    #[test]
+    fn parse_run_all() {
+        let expected = Command::Run(RunOptions {
+            days: Days::All,
+            part: Some(1),
+            input: None,
+            format: Format::Json,
+            parallel: true,
+            memory: false,
+            profile: Some("alice".to_string()),
+            timeout: None,
+        });
+        let command = "run --all -p 1 --format json --parallel --profile alice --timeout 0";
+        assert_eq!(Ok(expected), parse(&args(command)));
+    }
+
+    #[test]
+    fn parse_list() {
+        assert_eq!(Ok(Command::List), parse(&args("list")));
+    }
+
+    #[test]
+    fn parse_verify() {
+        let expected = Command::Verify(VerifyOptions {
+            answers: "other.txt".to_string(),
+            record: true,
+            profile: None,
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+        });
+        assert_eq!(Ok(expected), parse(&args("verify --record --answers other.txt")));
+
+        let expected = Command::Verify(VerifyOptions {
+            answers: "data/alice/answers.txt".to_string(),
+            record: false,
+            profile: Some("alice".to_string()),
+            timeout: None,
+        });
+        assert_eq!(Ok(expected), parse(&args("verify --profile alice --timeout 0")));
+    }
+
+    #[test]
+    fn parse_matrix() {
+        let expected = Command::Matrix(MatrixOptions {
+            part: None,
+            profiles: Vec::new(),
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+        });
+        assert_eq!(Ok(expected), parse(&args("matrix")));
+
+        let expected = Command::Matrix(MatrixOptions {
+            part: Some(2),
+            profiles: vec!["default".to_string(), "bob".to_string()],
+            timeout: Some(Duration::from_secs(30)),
+        });
+        assert_eq!(Ok(expected), parse(&args("matrix -p 2 --profile default --profile bob --timeout 30")));
+    }
+
+    #[test]
+    fn parse_bench() {
+        let expected = Command::Bench(BenchOptions {
+            days: Days::Day(9),
+            part: Some(2),
+            iterations: 50,
+            warmup: 0,
+            output: "now.txt".to_string(),
+            baseline: Some("then.txt".to_string()),
+            threshold: 5.0,
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+        });
+        let command = "bench 9 -p 2 -n 50 --warmup 0 -o now.txt --baseline then.txt --threshold 5%";
+        assert_eq!(Ok(expected), parse(&args(command)));
+    }
+
+    #[test]
+    fn parse_watch() {
+        let expected = Command::Watch(WatchOptions {
+            day: 12,
+            part: None,
+            input: None,
+            interval: watch::DEFAULT_INTERVAL,
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+        });
+        assert_eq!(Ok(expected), parse(&args("watch 12")));
+
+        let expected = Command::Watch(WatchOptions {
+            day: 3,
+            part: Some(1),
+            input: Some("other.txt".to_string()),
+            interval: 100,
+            timeout: Some(Duration::from_secs(2)),
+        });
+        assert_eq!(Ok(expected), parse(&args("watch 3 -p 1 -i other.txt --interval 100 --timeout 2")));
+    }
+
+    #[test]
+    fn parse_render() {
+        let expected = Command::Render(RenderOptions {
+            day: 10,
+            input: None,
+            output: None,
+            encoding: Encoding::Binary,
+            ascii: false,
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+        });
+        assert_eq!(Ok(expected), parse(&args("render 10")));
+
+        let expected = Command::Render(RenderOptions {
+            day: 3,
+            input: Some("a.txt".to_string()),
+            output: Some("fabric.ppm".to_string()),
+            encoding: Encoding::Plain,
+            ascii: false,
+            timeout: None,
+        });
+        assert_eq!(Ok(expected), parse(&args("render 3 -i a.txt --plain -o fabric.ppm --timeout 0")));
+
+        let expected = Command::Render(RenderOptions {
+            day: 12,
+            input: None,
+            output: None,
+            encoding: Encoding::Binary,
+            ascii: true,
+            timeout: Some(Duration::from_secs(DEFAULT_TIMEOUT)),
+        });
+        assert_eq!(Ok(expected), parse(&args("render --ascii 12")));
+    }
+
+    #[test]
+    fn parse_lint() {
+        let expected = Command::Lint(LintOptions { day: 4, input: None });
+        assert_eq!(Ok(expected), parse(&args("lint 4")));
+
+        let expected = Command::Lint(LintOptions { day: 4, input: Some("-".to_string()) });
+        assert_eq!(Ok(expected), parse(&args("lint 4 -")));
+    }
+
+    #[test]
+    fn parse_new() {
+        let expected = Command::New(NewOptions { day: 13, title: "Day 13".to_string() });
+        assert_eq!(Ok(expected), parse(&args("new 13")));
+
+        let expected = Command::New(NewOptions { day: 13, title: "Carts".to_string() });
+        assert_eq!(Ok(expected), parse(&args("new 13 --title Carts")));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse(&args("run")).is_err());
+        assert!(parse(&args("run 26")).is_err());
+        assert!(parse(&args("run 7 --part 3")).is_err());
+        assert!(parse(&args("run 7 --input")).is_err());
+        assert!(parse(&args("run --all --input other.txt")).is_err());
+        assert!(parse(&args("run 7 8")).is_err());
+        assert!(parse(&args("run 7 --format xml")).is_err());
+        assert!(parse(&args("run 7 --timeout soon")).is_err());
+        assert!(parse(&args("run --all --parallel --memory")).is_err());
+        assert!(parse(&args("list 7")).is_err());
+        assert!(parse(&args("verify 7")).is_err());
+        assert!(parse(&args("run 7 --input other.txt --profile alice")).is_err());
+        assert!(parse(&args("matrix --profile ../alice")).is_err());
+        assert!(parse(&args("bench")).is_err());
+        assert!(parse(&args("bench --all --iterations 0")).is_err());
+        assert!(parse(&args("bench --all --threshold fast")).is_err());
+        assert!(parse(&args("bench --all --timeout")).is_err());
+        assert!(parse(&args("watch")).is_err());
+        assert!(parse(&args("watch --all")).is_err());
+        assert!(parse(&args("watch 3 --input -")).is_err());
+        assert!(parse(&args("lint")).is_err());
+        assert!(parse(&args("render")).is_err());
+        assert!(parse(&args("render 10 --ascii --plain")).is_err());
+        assert!(parse(&args("render 10 --output a.pbm --ascii")).is_err());
+        assert!(parse(&args("lint 4 a.txt b.txt")).is_err());
+        assert!(parse(&args("new")).is_err());
+        assert!(parse(&args("new 13 --title")).is_err());
+        assert!(parse(&args("frobnicate")).is_err());
+    }
+}
@@ -1,30 +1,50 @@
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-pub fn part1(filename: &str) -> i32 {
-    let file = File::open(filename).expect("file not found");
-    let mut total = 0;
-    for line in BufReader::new(file).lines() {
-        total += line.unwrap().parse::<i32>().unwrap();
+use cancel;
+use input::{self, UNNAMED};
+use lint::{self, Rule};
+use {AdventError, Grammar, Input, Result, Solution};
+
+fn parse_frequencies<R: BufRead>(reader: R) -> Result<Vec<i32>> {
+    let mut values: Vec<i32> = Vec::new();
+    for (i, line) in input::lines(reader).enumerate() {
+        let data = line?;
+        let value = data.parse::<i32>()
+            .map_err(|_| AdventError::parse(UNNAMED, i + 1, 1, &data, "not an int"))?;
+        values.push(value);
     }
-    total
+    Ok(values)
 }
 
-pub fn part2(filename: &str) -> i32 {
+pub fn part1(filename: &str) -> Result<i32> {
+    input::with_file(filename, part1_from)
+}
+
+pub fn part1_from<R: BufRead>(reader: R) -> Result<i32> {
+    let values = parse_frequencies(reader)?;
+    Ok(values.iter().sum())
+}
+
+pub fn part2(filename: &str) -> Result<i32> {
+    input::with_file(filename, part2_from)
+}
+
+pub fn part2_from<R: BufRead>(reader: R) -> Result<i32> {
     // parse the data into a vector of i32s
-    let file = File::open(filename).expect("file not found");
-    let mut values: Vec<i32> = Vec::new();
-    for line in BufReader::new(file).lines() {
-        let value = line.unwrap().parse::<i32>().unwrap();
-        values.push(value);
+    let values = parse_frequencies(reader)?;
+    if values.is_empty() {
+        return Err(AdventError::no_answer("no frequency changes"));
     }
 
     // find duplicate frequency
     let mut frequencies: HashSet<i32> = HashSet::new();
     let mut total = 0;
+    // the starting frequency counts as reached
+    frequencies.insert(total);
     let mut found = false;
     while !found {
+        cancel::check()?;
         for value in &values {
             total += value;
             if frequencies.contains(&total) {
@@ -35,5 +55,57 @@ pub fn part2(filename: &str) -> i32 {
             }
         }
     }
-    total
+    Ok(total)
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    fn day(&self) -> u32 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Calibration"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day1-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^[+-]\d+$", "a frequency change such as '+7'"))
+            .with_check(|lines| lint::each_line(lines, |line| parse_frequencies(line.as_bytes())))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(3, part1_from("+1\n-2\n+3\n+1".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(2, part2_from("+1\n-2\n+3\n+1".as_bytes()).unwrap());
+        assert_eq!(0, part2_from("+1\n-1".as_bytes()).unwrap());
+        assert_eq!(10, part2_from("+3\n+3\n+4\n-2\n-4".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part1_not_an_int() {
+        let e = part1_from("+1\nabc".as_bytes()).unwrap_err();
+        assert_eq!("<input>:2:1: not an int: 'abc'", e.to_string());
+    }
 }
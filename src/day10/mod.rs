@@ -1,137 +1,213 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-use regex::Regex;
+use geometry::{BoundingBox, Point, Vector};
+use input;
+use lint::{self, Rule};
+use parse::LineParser;
+use render::{self, Image};
+use simulation::{Driver, Simulation};
+use {AdventError, Grammar, Input, Result, Solution};
 
 #[derive(Debug)]
 struct Light {
-    x: i32,
-    y: i32,
-    v_x: i32,
-    v_y: i32,
+    position: Point,
+    velocity: Vector,
 }
 
 impl Light {
     fn step(&mut self) {
-        self.x += self.v_x;
-        self.y += self.v_y;
+        self.position += self.velocity;
     }
 
     fn back(&mut self) {
-        self.x -= self.v_x;
-        self.y -= self.v_y;
+        self.position -= self.velocity;
     }
 }
 
-fn parse_file(filename: &str) -> Vec<Light> {
-    let mut lights = Vec::new();
-    let file = File::open(filename).expect("file not found");
+fn parse_file<R: BufRead>(reader: R) -> Result<Vec<Light>> {
     // file format: position=<-50948,  20587> velocity=< 5, -2>
-    let regex = Regex::new(r"position=<\s*(-?\d+),\s*(-?\d+)> velocity=<\s*(-?\d+),\s*(-?\d+)>").expect("bad regex");
-    for line in BufReader::new(file).lines() {
-        let data = line.unwrap();
-        let caps = match regex.captures(&data) {
-            None => panic!("line {} does not match regex", data),
-            Some(c) => c,
-        };
-
-        let light = Light {
-            x: caps[1].parse::<i32>().expect("not an int"),
-            y: caps[2].parse::<i32>().expect("not an int"),
-            v_x: caps[3].parse::<i32>().expect("not an int"),
-            v_y: caps[4].parse::<i32>().expect("not an int"),
-        };
-        lights.push(light);
+    let parser = LineParser::new(r"position=<\s*(-?\d+),\s*(-?\d+)> velocity=<\s*(-?\d+),\s*(-?\d+)>");
+    let lights: Vec<Light> = parser.parse::<(i32, i32, i32, i32), _>(reader)?
+        .into_iter()
+        .map(|(x, y, v_x, v_y)| Light {
+            position: Point::new(x, y),
+            velocity: Vector::new(v_x, v_y),
+        })
+        .collect();
+
+    if lights.is_empty() {
+        return Err(AdventError::no_answer("no lights"));
     }
+    Ok(lights)
+}
 
-    lights
+fn bounds(lights: &[Light]) -> BoundingBox {
+    BoundingBox::from_points(lights.iter().map(|l| l.position)).expect("no lights")
 }
 
-fn bounds(lights: &Vec<Light>) -> (i32, i32, i32, i32) {
-    let mut minx = lights[0].x;
-    let mut miny = lights[0].y;
-    let mut maxx = lights[0].x;
-    let mut maxy = lights[0].y;
-    for l in lights {
-        if l.x < minx {
-            minx = l.x;
-        }
-        if l.y < miny {
-            miny = l.y;
-        }
-        if l.x > maxx {
-            maxx = l.x;
+fn render_lights(lights: &[Light]) -> Image {
+    Image::Bitmap(render::points(lights.iter().map(|l| l.position)))
+}
+
+/// Every light moving at once, a step is one second.
+struct Sky {
+    lights: Vec<Light>,
+}
+
+impl Simulation for Sky {
+    /// Where each light is.
+    type State = Vec<Point>;
+
+    fn step(&mut self) {
+        for l in &mut self.lights {
+            l.step();
         }
-        if l.y > maxy {
-            maxy = l.y;
+    }
+
+    fn undo(&mut self) -> bool {
+        for l in &mut self.lights {
+            l.back();
         }
+        true
+    }
+
+    fn snapshot(&self) -> Vec<Point> {
+        self.lights.iter().map(|l| l.position).collect()
     }
-    (minx, miny, maxx, maxy)
 }
 
-fn print_lights(lights: &Vec<Light>) {
-    let (minx, miny, maxx, maxy) = bounds(&lights);
+fn find_message<R: BufRead>(reader: R) -> Result<(Vec<Light>, u64)> {
+    let mut sky = Driver::new(Sky { lights: parse_file(reader)? });
 
-    let width = (maxx + 1 - minx) as usize;
-    let height = (maxy + 1 - miny) as usize;
+    // find where the lights are closest to each other, the sky grows again one second later
+    let mut last_size = None;
+    sky.run_until(|sky| {
+        let bounds = bounds(&sky.lights);
+        let size = bounds.max - bounds.min;
+        let grew = last_size.is_some_and(|last: Vector| size.x > last.x || size.y > last.y);
+        last_size = Some(size);
+        grew
+    })?;
 
-    let mut points: Vec<char> = vec!['.'; width * height];
+    // step back once to get correct image
+    sky.undo();
+    let count = sky.steps();
 
-    for l in lights {
-        let x = (l.x - minx) as usize;
-        let y = (l.y - miny) as usize;
-        let i: usize = y * width + x;
-        points[i] = '#';
-    }
+    Ok((sky.into_simulation().lights, count))
+}
 
-    let mut out = String::new();
-    for y in 0..height {
-        for x in 0..width {
-            let i: usize = y * width + x;
-            out.push(points[i]);
-        }
-        out.push('\n');
-    }
+pub fn part1(filename: &str) -> Result<String> {
+    input::with_file(filename, part1_from)
+}
 
-    println!("{}", &out);
+pub fn part1_from<R: BufRead>(reader: R) -> Result<String> {
+    let (lights, _) = find_message(reader)?;
+    Ok(render_lights(&lights).to_ascii())
 }
 
-pub fn part1_and_2(filename: &str) -> usize {
-    let mut lights = parse_file(filename);
+pub fn part2(filename: &str) -> Result<u64> {
+    input::with_file(filename, part2_from)
+}
 
-    let mut count = 0;
-    let mut last_diffx = 0;
-    let mut last_diffy = 0;
+pub fn part2_from<R: BufRead>(reader: R) -> Result<u64> {
+    let (_, count) = find_message(reader)?;
+    Ok(count)
+}
 
-    // find where the lights are closest to each other
-    loop {
-        count += 1;
-        for l in &mut lights {
-            l.step();
-        }
-        let (minx, miny, maxx, maxy) = bounds(&lights);
-        let diffx = maxx - minx;
-        let diffy = maxy - miny;
+/// The message the lights spell out.
+pub fn render_from<R: BufRead>(reader: R) -> Result<Image> {
+    let (lights, _) = find_message(reader)?;
+    Ok(render_lights(&lights))
+}
 
-        if last_diffx != 0 && diffx > last_diffx {
-            break;
-        }
-        if last_diffy != 0 && diffy > last_diffy {
-            break;
-        }
+pub struct Day10;
 
-        last_diffx = diffx;
-        last_diffy = diffy;
+impl Solution for Day10 {
+    fn day(&self) -> u32 {
+        10
     }
 
-    // step back once to get correct image
-    count -= 1;
-    for l in &mut lights {
-        l.back();
+    fn title(&self) -> &'static str {
+        "The Stars Align"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day10-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^position=<\s*-?\d+,\s*-?\d+> velocity=<\s*-?\d+,\s*-?\d+>$",
+                                  "a light such as 'position=< 9,  1> velocity=< 0,  2>'"))
+            .with_check(|lines| lint::each_line(lines, |line| parse_file(line.as_bytes())))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
     }
 
-    println!("Seconds: {}", count);
-    print_lights(&lights);
+    fn render(&self, input: &str) -> Result<Image> {
+        render_from(input.as_bytes())
+    }
+}
 
-    count
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>
+position=< 6, 10> velocity=<-2, -1>
+position=< 2, -4> velocity=< 2,  2>
+position=<-6, 10> velocity=< 2, -2>
+position=< 1,  8> velocity=< 1, -1>
+position=< 1,  7> velocity=< 1,  0>
+position=<-3, 11> velocity=< 1, -2>
+position=< 7,  6> velocity=<-1, -1>
+position=<-2,  3> velocity=< 1,  0>
+position=<-4,  3> velocity=< 2,  0>
+position=<10, -3> velocity=<-1,  1>
+position=< 5, 11> velocity=< 1, -2>
+position=< 4,  7> velocity=< 0, -1>
+position=< 8, -2> velocity=< 0,  1>
+position=<15,  0> velocity=<-2,  0>
+position=< 1,  6> velocity=< 1,  0>
+position=< 8,  9> velocity=< 0, -1>
+position=< 3,  3> velocity=<-1,  1>
+position=< 0,  5> velocity=< 0, -1>
+position=<-2,  2> velocity=< 2,  0>
+position=< 5, -2> velocity=< 1,  2>
+position=< 1,  4> velocity=< 2,  1>
+position=<-2,  7> velocity=< 2, -2>
+position=< 3,  6> velocity=<-1, -1>
+position=< 5,  0> velocity=< 1,  0>
+position=<-6,  0> velocity=< 2,  0>
+position=< 5,  9> velocity=< 1, -2>
+position=<14,  7> velocity=<-2,  0>
+position=<-3,  6> velocity=< 2, -1>";
+
+    #[test]
+    fn part1_example() {
+        // the message reads "HI"
+        let expected = "\
+#...#..###
+#...#...#.
+#...#...#.
+#####...#.
+#...#...#.
+#...#...#.
+#...#...#.
+#...#..###
+";
+        assert_eq!(expected, part1_from(EXAMPLE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(3, part2_from(EXAMPLE.as_bytes()).unwrap());
+    }
 }
@@ -1,7 +1,16 @@
 use std::cmp::Ordering;
+use std::io::BufRead;
 
 use rayon::prelude::*;
 
+use geometry::{BoundingBox, Point};
+use input::{self, UNNAMED};
+use grid::Grid;
+use lint::{self, Rule};
+use render::{self, Image, Palette};
+use summed_area::SummedAreaTable;
+use {AdventError, Grammar, Input, Result, Solution};
+
 #[derive(Debug)]
 pub struct Answer {
     x: usize,
@@ -12,7 +21,7 @@ pub struct Answer {
 
 impl PartialOrd for Answer {
     fn partial_cmp(&self, other: &Answer) -> Option<Ordering> {
-        Some(self.power.cmp(&other.power))
+        Some(self.cmp(other))
     }
 }
 
@@ -40,6 +49,10 @@ impl Answer {
     }
 }
 
+/// The largest serial number accepted, puzzle serials have four digits and anything much larger
+/// would overflow the power level calculation.
+const MAX_SERIAL: usize = 1_000_000;
+
 const GRID_WIDTH: usize = 300;
 const GRID_HEIGHT: usize = 300;
 
@@ -51,44 +64,18 @@ fn calculate_power(x: usize, y: usize, serial: usize) -> i32 {
     // Increase the power level by the value of the grid serial number (your puzzle input).
     power += serial;
     // Set the power level to itself multiplied by the rack ID.
-    power = power * rack_id;
+    power *= rack_id;
     // Keep only the hundreds digit of the power level (so 12345 becomes 3; numbers with no hundreds digit become 0).
     power = (power % 1000) / 100;
     // Subtract 5 from the power level.
     power as i32 - 5
 }
 
-fn generate_summed_area_grid(serial: usize) -> [i32; GRID_WIDTH * GRID_HEIGHT] {
-    let mut points = [0; GRID_WIDTH * GRID_HEIGHT];
-    for y in 1..=GRID_HEIGHT {
-        for x in 1..=GRID_HEIGHT {
-            let index = (y - 1) * GRID_WIDTH + (x - 1);
-            points[index] = calculate_power(x, y, serial);
-        }
-    }
-
-    let mut summed_area = [0; GRID_WIDTH * GRID_HEIGHT];
-    for y in 0..GRID_WIDTH {
-        let index = y * GRID_WIDTH;
-        summed_area[index] = points[index];
-    }
-
-    for x in 1..GRID_WIDTH {
-        for y in 0..GRID_HEIGHT {
-            let index = y * GRID_WIDTH + x;
-            summed_area[index] = points[index] + summed_area[index - 1];
-        }
-    }
-
-    for x in 0..GRID_WIDTH {
-        for y in 1..GRID_HEIGHT {
-            let index = y * GRID_WIDTH + x;
-            let previous_index = (y - 1) * GRID_WIDTH + x;
-            summed_area[index] = summed_area[index] + summed_area[previous_index];
-        }
-    }
-
-    summed_area
+/// Builds a summed-area table of the power levels, using the puzzle's coordinates from `(1, 1)`.
+fn generate_summed_area_grid(serial: usize) -> SummedAreaTable<i32> {
+    SummedAreaTable::from_fn(GRID_WIDTH, GRID_HEIGHT, |p| {
+        calculate_power(p.x as usize + 1, p.y as usize + 1, serial)
+    }).with_origin(Point::new(1, 1))
 }
 
 fn find_largest_3x3(serial: usize) -> Answer {
@@ -110,13 +97,13 @@ fn find_largest_any_size_parallel(serial: usize) -> Answer {
     (2..GRID_WIDTH).into_par_iter().map(|s| largest_square(s, &points)).max().unwrap()
 }
 
-fn largest_square(size: usize, points: &[i32; GRID_WIDTH * GRID_HEIGHT]) -> Answer {
+fn largest_square(size: usize, points: &SummedAreaTable<i32>) -> Answer {
     let mut answer = Answer::empty();
     answer.size = size;
 
-    for y in 2..=GRID_HEIGHT - (size - 1) {
-        for x in 2..=GRID_HEIGHT - (size - 1) {
-            let power = sum_square(size, x - 1, y - 1, &points);
+    for y in 1..=GRID_HEIGHT - (size - 1) {
+        for x in 1..=GRID_WIDTH - (size - 1) {
+            let power = sum_square(size, x, y, points);
             if power > answer.power {
                 answer.power = power;
                 answer.x = x;
@@ -128,39 +115,108 @@ fn largest_square(size: usize, points: &[i32; GRID_WIDTH * GRID_HEIGHT]) -> Answ
     answer
 }
 
-fn sum_square(size: usize, left_x: usize, left_y: usize, points: &[i32; GRID_WIDTH * GRID_HEIGHT]) -> i32 {
-    let left = left_x - 1;
-    let right = left_x + size - 1;
-    let top = left_y - 1;
-    let bottom = left_y + size - 1;
+/// Returns the total power of the square whose top left fuel cell is at `(x, y)`.
+fn sum_square(size: usize, x: usize, y: usize, points: &SummedAreaTable<i32>) -> i32 {
+    let top_left = Point::new(x as i32, y as i32);
+    let bottom_right = Point::new(top_left.x + size as i32 - 1, top_left.y + size as i32 - 1);
+    points.sum(&BoundingBox::new(top_left, bottom_right))
+}
+
+fn parse_serial<R: BufRead>(reader: R) -> Result<usize> {
+    // file format: 8199
+    let data = match input::lines(reader).next() {
+        None => String::new(),
+        Some(line) => line?,
+    };
+    let serial = data.trim().parse::<usize>()
+        .map_err(|_| AdventError::parse(UNNAMED, 1, 1, &data, "not a serial number"))?;
+    if serial > MAX_SERIAL {
+        let reason = format!("the serial number is at most {}", MAX_SERIAL);
+        return Err(AdventError::parse(UNNAMED, 1, 1, &data, &reason));
+    }
+    Ok(serial)
+}
 
-    let left_top_index = top * GRID_WIDTH + left;
-    let right_top_index = top * GRID_WIDTH + right;
-    let left_bottom_index = bottom * GRID_WIDTH + left;
-    let right_bottom_index = bottom * GRID_WIDTH + right;
+pub fn part1(filename: &str) -> Result<Answer> {
+    input::with_file(filename, part1_from)
+}
+
+pub fn part1_from<R: BufRead>(reader: R) -> Result<Answer> {
+    let serial = parse_serial(reader)?;
+    Ok(find_largest_3x3(serial))
+}
+
+pub fn part2(filename: &str) -> Result<Answer> {
+    input::with_file(filename, part2_from)
+}
 
-    points[right_bottom_index] - points[right_top_index] - points[left_bottom_index] + points[left_top_index]
+pub fn part2_from<R: BufRead>(reader: R) -> Result<Answer> {
+    let serial = parse_serial(reader)?;
+    Ok(find_largest_any_size_parallel(serial))
 }
 
-pub fn part1() -> Answer {
-    find_largest_3x3(8199)
+/// A heatmap of the power level of every fuel cell.
+pub fn render_from<R: BufRead>(reader: R) -> Result<Image> {
+    let serial = parse_serial(reader)?;
+    let power = Grid::from_fn(GRID_WIDTH, GRID_HEIGHT, |p| calculate_power(p.x as usize + 1, p.y as usize + 1, serial))
+        .with_origin(Point::new(1, 1));
+    Ok(Image::Pixmap(render::heatmap(&power, &Palette::heat(), |&p| p as f64)))
 }
 
-pub fn part2() -> Answer {
-    find_largest_any_size_parallel(8199)
+pub struct Day11;
+
+impl Solution for Day11 {
+    fn day(&self) -> u32 {
+        11
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Charge"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day11-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::line(Rule::new(r"^\d+$", "a grid serial number"))
+            .with_check(|lines| lint::each_line(lines, |line| parse_serial(line.as_bytes())))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        let answer = part1_from(input.as_bytes())?;
+        Ok(format!("{},{}", answer.x, answer.y))
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        let answer = part2_from(input.as_bytes())?;
+        Ok(format!("{},{},{}", answer.x, answer.y, answer.size))
+    }
+
+    fn render(&self, input: &str) -> Result<Image> {
+        render_from(input.as_bytes())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "nightly")]
     use test::Bencher;
 
     use super::*;
 
+    /// `Answer` only compares the power, so tests look at every field.
+    fn fields(answer: Answer) -> (usize, usize, usize, i32) {
+        (answer.x, answer.y, answer.size, answer.power)
+    }
+
+    #[cfg(feature = "nightly")]
     #[bench]
     fn find_largest_any_size_bench(b: &mut Bencher) {
         b.iter(|| find_largest_any_size(18));
     }
 
+    #[cfg(feature = "nightly")]
     #[bench]
     fn find_largest_any_size_parallel_bench(b: &mut Bencher) {
         b.iter(|| find_largest_any_size_parallel(18));
@@ -181,23 +237,35 @@ mod tests {
         assert_eq!(4, calculate_power(101, 153, 71));
     }
 
+    #[test]
+    fn part1_from_example() {
+        assert_eq!("33,45", Day11.part1("18\n").unwrap());
+    }
+
+    #[test]
+    fn serial_too_large() {
+        let e = Day11.part1("18446744073709551615\n").unwrap_err();
+        assert_eq!("<input>:1:1: the serial number is at most 1000000: '18446744073709551615'", e.to_string());
+        assert!(Day11.part1("1000000\n").is_ok());
+    }
+
     #[test]
     fn find_largest_3x3_example1() {
-        assert_eq!(Answer::new(33, 45, 3, 29), find_largest_3x3(18));
+        assert_eq!((33, 45, 3, 29), fields(find_largest_3x3(18)));
     }
 
     #[test]
     fn find_largest_3x3_example2() {
-        assert_eq!(Answer::new(21, 61, 3, 30), find_largest_3x3(42));
+        assert_eq!((21, 61, 3, 30), fields(find_largest_3x3(42)));
     }
 
     #[test]
     fn find_largest_any_size_example1() {
-        assert_eq!(Answer::new(90, 269, 16, 113), find_largest_any_size_parallel(18));
+        assert_eq!((90, 269, 16, 113), fields(find_largest_any_size_parallel(18)));
     }
 
     #[test]
     fn find_largest_any_size_example2() {
-        assert_eq!(Answer::new(232, 251, 12, 119), find_largest_any_size_parallel(42));
+        assert_eq!((232, 251, 12, 119), fields(find_largest_any_size_parallel(42)));
     }
 }
@@ -1,205 +1,176 @@
-use std::collections::HashMap;
-
-use regex::Regex;
+use std::convert::TryFrom;
+use std::io::BufRead;
+
+use automaton::{Automaton, Rules};
+use geometry::Point;
+use input::{self, UNNAMED};
+use lint::Rule;
+use parse::LineParser;
+use render::{self, Image};
+use simulation::Driver;
+use {AdventError, Grammar, Input, Result, Solution};
+
+/// Pots look at the two pots on either side of them.
+const RADIUS: usize = 2;
+const EMPTY: u8 = 0;
+const PLANT: u8 = 1;
+const PART1_GENERATIONS: u64 = 20;
+const PART2_GENERATIONS: u64 = 50_000_000_000;
+
+fn pot(c: char) -> Option<u8> {
+    match c {
+        '#' => Some(PLANT),
+        '.' => Some(EMPTY),
+        _ => None,
+    }
+}
 
-struct Pots {
-    left: i32,
-    state: Vec<u8>,
-    rules: HashMap<u8, u8>,
+/// Where the plants are, with the generation as the `y` coordinate.
+fn plants(pots: &Automaton, generation: u64) -> impl Iterator<Item=Point> + '_ {
+    pots.iter().filter(|&(_, p)| p == PLANT).map(move |(i, _)| Point::new(i as i32, generation as i32))
 }
 
-impl Pots {
-    pub fn process_generations(&mut self, count: usize) {
-        for _ in 0..count {
-            self.next_generation();
-        }
-    }
+/// The sum of the numbers of every pot that contains a plant.
+fn sum(pots: &Automaton) -> i64 {
+    pots.iter().filter(|&(_, p)| p == PLANT).map(|(i, _)| i).sum()
+}
 
-    pub fn next_generation(&mut self) {
-        let mut new_state: Vec<u8> = Vec::new();
-        let mut found_pot = false;
-        for i in 0..self.state.len() + 4 {
-            let v = from_vec_to_u8(i as i32 - 2, &self.state);
-
-            let c = match self.rules.get(&v) {
-                None => 0,
-                Some(c) => *c,
-            };
-            // trim front
-            if !found_pot && c == 0 {
-                if i > 1 {
-                    self.left += 1;
-                }
-                continue;
-            }
-            if i < 2 && c == 1 {
-                self.left -= 1;
-            }
-            found_pot = true;
-            new_state.push(c);
-        }
+/*
+File Format:
+initial state: #..#.#..##......###...###
 
-        // clean up empty pots on end
-        loop {
-            let index = new_state.len() - 1;
-            if new_state[index] == 0 {
-                new_state.remove(index);
-            } else {
-                break;
-            }
-        }
+...## => #
+..#.. => #
+*/
+fn parse_state<R: BufRead>(reader: R) -> Result<Automaton> {
+    let mut lines = input::lines(reader);
 
-        self.state = new_state;
+    // parse state
+    const STATE_PREFIX: &str = "initial state: ";
+    let state_line = match lines.next() {
+        None => String::new(),
+        Some(line) => line?,
+    };
+    if !state_line.starts_with(STATE_PREFIX) {
+        return Err(AdventError::parse(UNNAMED, 1, 1, &state_line, "expected the initial state"));
     }
-
-    #[allow(dead_code)]
-    pub fn print(&self) {
-        let mut s = String::new();
-        for v in &self.state {
-            if *v == 1 {
-                s.push('#');
-            } else {
-                s.push('.');
+    let mut state = Vec::new();
+    for (column, c) in state_line[STATE_PREFIX.len()..].chars().enumerate() {
+        match pot(c) {
+            Some(p) => state.push(p),
+            None => {
+                let column = STATE_PREFIX.len() + column + 1;
+                return Err(AdventError::parse(UNNAMED, 1, column, &c.to_string(), "not a pot"));
             }
         }
-        println!("offset: {} state: {}", self.left, s);
     }
 
-    pub fn sum(&self) -> i32 {
-        let mut total = 0;
-        for i in 0..self.state.len() {
-            if self.state[i] == 1 {
-                total += i as i32 + self.left;
-            }
+    // parse rules
+    let mut rules = Rules::new(RADIUS, 2);
+    let parser = LineParser::new(r"^([.#]{5}) => ([.#])$");
+
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        // rules start after the blank line on line 2
+        if i == 0 && line.is_empty() {
+            continue;
         }
-        total
+        let (pattern, next): (String, char) = parser.parse_line(i + 2, &line)?;
+        let neighbourhood: Vec<u8> = pattern.chars().filter_map(pot).collect();
+        rules.set(&neighbourhood, pot(next).expect("the pattern only matches pots"));
+    }
+    if rules.get(&[EMPTY; 2 * RADIUS + 1]) != EMPTY {
+        return Err(AdventError::no_answer("every empty pot grows a plant"));
     }
+
+    Ok(Automaton::new(rules, &state))
 }
 
-fn from_vec_to_u8(index: i32, state: &Vec<u8>) -> u8 {
-    let l = state.len() as i32;
-    let pos_0 = if (index - 2) < 0 { 0 } else { state[(index - 2) as usize] };
-    let pos_1 = if (index - 1) < 0 || (index - 1) >= l { 0 } else { state[(index - 1) as usize] };
-    let pos_2 = if index < 0 || index >= l { 0 } else { state[index as usize] };
-    let pos_3 = if (index + 1) < 0 || (index + 1) >= l { 0 } else { state[(index + 1) as usize] };
-    let pos_4 = if (index + 2) >= l { 0 } else { state[(index + 2) as usize] };
-
-    (pos_0 << 4) +
-        (pos_1 << 3) +
-        (pos_2 << 2) +
-        (pos_3 << 1) +
-        pos_4
+pub fn part1(filename: &str) -> Result<i64> {
+    input::with_file(filename, part1_from)
 }
 
-fn from_str_to_u8(s: &str) -> u8 {
-    let v = s.replace(".", "0").replace("#", "1");
-    u8::from_str_radix(&v, 2).unwrap()
+pub fn part1_from<R: BufRead>(reader: R) -> Result<i64> {
+    let mut pots = Driver::new(parse_state(reader)?);
+    pots.step_to(PART1_GENERATIONS)?;
+    Ok(sum(pots.simulation()))
 }
 
-fn parse_state(state_s: &str, rules_s: &str) -> Pots {
-    // parse state
-    let mut state = Vec::new();
-    for c in state_s.chars() {
-        if c == '#' {
-            state.push(1);
-        } else {
-            state.push(0);
-        }
+pub fn part2(filename: &str) -> Result<u128> {
+    input::with_file(filename, part2_from)
+}
+
+pub fn part2_from<R: BufRead>(reader: R) -> Result<u128> {
+    let mut pots = Driver::new(parse_state(reader)?);
+    let mut last = sum(pots.simulation());
+    let mut last_diff = None;
+
+    // find generation where delta is the same as previous generation
+    let count = pots.run_until(|pots| {
+        let s = sum(pots);
+        let diff = s - last;
+        last = s;
+        last_diff.replace(diff) == Some(diff)
+    })?;
+    let diff = last_diff.expect("at least one generation");
+
+    let total = (PART2_GENERATIONS as i128 - count as i128) * diff as i128 + sum(pots.simulation()) as i128;
+    u128::try_from(total).map_err(|_| AdventError::no_answer("the plants drift into negative pots"))
+}
+
+/// The pots of the first 20 generations, one generation per row.
+pub fn render_from<R: BufRead>(reader: R) -> Result<Image> {
+    let pots = parse_state(reader)?;
+    let mut history: Vec<Point> = plants(&pots, 0).collect();
+    Driver::new(pots)
+        .observe(|generation, pots| history.extend(plants(pots, generation)))
+        .step_to(PART1_GENERATIONS)?;
+    Ok(Image::Bitmap(render::points(history)))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    fn day(&self) -> u32 {
+        12
     }
 
-    // parse rules
-    let mut rules: HashMap<u8, u8> = HashMap::new();
-    let regex = Regex::new(r"(.*) => (.*)").expect("bad regex");
-
-    for line in rules_s.lines() {
-        let caps = match regex.captures(&line) {
-            None => panic!("line {} does not match regex", line),
-            Some(c) => c,
-        };
-        let a = from_str_to_u8(&caps[1]);
-        let b = caps[2].parse::<char>().expect("not a char");
-        if b == '#' {
-            rules.insert(a, 1);
-        } else {
-            rules.insert(a, 0);
-        }
+    fn title(&self) -> &'static str {
+        "Subterranean Sustainability"
     }
 
-    Pots {
-        left: 0,
-        state: state,
-        rules: rules,
+    fn input(&self) -> Input {
+        Input::File("data/day12-input.txt")
     }
-}
 
-pub fn part1() -> i32 {
-    let mut pots = parse_state(PART1_STATE, PART1_RULES);
-    pots.process_generations(20);
-    pots.sum()
-}
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^[.#]{5} => [.#]$", "a rule such as '...## => #'"))
+            .headed_by(vec![
+                Rule::new(r"^initial state: [.#]*$", "the initial state such as 'initial state: #..#.#'"),
+                Rule::new(r"^$", "a blank line"),
+            ])
+    }
 
-pub fn part2() -> u128 {
-    let mut pots = parse_state(PART1_STATE, PART1_RULES);
-    let mut count = 0;
-    let mut last = pots.sum();
-    let mut last_diff = 0;
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
 
-    // find generation where delta is the same as previous generation
-    loop {
-        count += 1;
-        pots.next_generation();
-        let s = pots.sum();
-        let diff = s - last;
-        if diff == last_diff {
-            break;
-        }
-        last = s;
-        last_diff = diff;
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
     }
 
-    (50000000000 as u128 - count) * last_diff as u128 + pots.sum() as u128
+    fn render(&self, input: &str) -> Result<Image> {
+        render_from(input.as_bytes())
+    }
 }
 
-const PART1_STATE: &str = "##..#.#.#..##..#..##..##..#.#....#.....##.#########...#.#..#..#....#.###.###....#..........###.#.#..";
-const PART1_RULES: &str = r#"..##. => .
-..... => .
-##..# => .
-...#. => .
-#.... => .
-...## => #
-.#.#. => .
-#..#. => #
-##.#. => .
-#..## => .
-..#.. => .
-#.#.# => .
-###.# => .
-###.. => .
-.#... => #
-.##.# => .
-##... => #
-..### => .
-####. => .
-#...# => #
-.#..# => #
-##### => #
-..#.# => #
-.#.## => #
-#.### => .
-....# => .
-.###. => .
-.#### => #
-.##.. => .
-##.## => #
-#.##. => #
-#.#.. => #"#;
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE_STATE: &str = "#..#.#..##......###...###";
-    const EXAMPLE_RULES: &str = r#"...## => #
+    const EXAMPLE_INPUT: &str = r#"initial state: #..#.#..##......###...###
+
+...## => #
 ..#.. => #
 .#... => #
 .#.#. => #
@@ -216,8 +187,37 @@ mod tests {
 
     #[test]
     fn example1() {
-        let mut pots = parse_state(EXAMPLE_STATE, EXAMPLE_RULES);
-        pots.process_generations(20);
-        assert_eq!(325, pots.sum());
+        let mut pots = parse_state(EXAMPLE_INPUT.as_bytes()).unwrap();
+        pots.steps(20);
+        assert_eq!(325, sum(&pots));
+    }
+
+    #[test]
+    fn part1_from_example() {
+        assert_eq!(325, part1_from(EXAMPLE_INPUT.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn drifting_left() {
+        // a single plant moves one pot to the left every generation
+        let e = part2_from("initial state: #\n\n...#. => #".as_bytes()).unwrap_err();
+        assert_eq!("no answer: the plants drift into negative pots", e.to_string());
+    }
+
+    #[test]
+    fn render_generations() {
+        let image = render_from(EXAMPLE_INPUT.as_bytes()).unwrap();
+        assert_eq!(21, image.height());
+        let ascii = image.to_ascii();
+        let rows: Vec<&str> = ascii.lines().collect();
+        // the plants spread two pots to the left before generation 20
+        assert!(rows[0].starts_with("..#..#.#..##......###...###"));
+        assert!(rows[20].starts_with("#....##....#####"));
+    }
+
+    #[test]
+    fn missing_initial_state() {
+        let e = part1_from("...## => #".as_bytes()).unwrap_err();
+        assert_eq!("<input>:1:1: expected the initial state: '...## => #'", e.to_string());
     }
 }
\ No newline at end of file
@@ -1,15 +1,36 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-pub fn part1(filename: &str) -> i32 {
-    let file = File::open(filename).expect("file not found");
+use input::{self, UNNAMED};
+use lint::Rule;
+use {AdventError, Grammar, Input, Result, Solution};
+
+fn parse_box_ids<R: BufRead>(reader: R) -> Result<Vec<String>> {
+    input::lines(reader).collect()
+}
+
+/// Part 2 compares ids letter by letter, so they must all have the same length as the first.
+fn check_ids(lines: &[(usize, &str)]) -> Vec<AdventError> {
+    let length = lines.first().map_or(0, |&(_, id)| id.len());
+    let reason = format!("expected an id of {} letters", length);
+    lines.iter()
+        .filter(|&&(_, id)| id.len() != length)
+        .map(|&(line_number, id)| AdventError::parse(UNNAMED, line_number, 1, id, &reason))
+        .collect()
+}
+
+pub fn part1(filename: &str) -> Result<i32> {
+    input::with_file(filename, part1_from)
+}
+
+pub fn part1_from<R: BufRead>(reader: R) -> Result<i32> {
+    let ids = parse_box_ids(reader)?;
 
     let mut two_letters = 0;
     let mut three_letters = 0;
     let mut letters: HashMap<char, i32> = HashMap::new();
-    for line in BufReader::new(file).lines() {
-        for c in line.unwrap().chars() {
+    for id in ids {
+        for c in id.chars() {
             let count = letters.entry(c).or_insert(0);
             *count += 1;
         }
@@ -33,33 +54,39 @@ pub fn part1(filename: &str) -> i32 {
 
         letters.clear();
     }
-    two_letters * three_letters
+    Ok(two_letters * three_letters)
+}
+
+pub fn part2(filename: &str) -> Result<String> {
+    input::with_file(filename, part2_from)
 }
 
-pub fn part2(filename: &str) -> String {
+pub fn part2_from<R: BufRead>(reader: R) -> Result<String> {
     // parse all lines into a vector of chars
-    let file = File::open(filename).expect("file not found");
     let mut boxes: Vec<Vec<char>> = Vec::new();
-    for line in BufReader::new(file).lines() {
-        let mut box_chars: Vec<char> = Vec::new();
-        for c in line.unwrap().chars() {
-            box_chars.push(c);
+    for (i, id) in parse_box_ids(reader)?.iter().enumerate() {
+        let box_chars: Vec<char> = id.chars().collect();
+        if !boxes.is_empty() && box_chars.len() != boxes[0].len() {
+            let reason = format!("expected an id of {} letters", boxes[0].len());
+            return Err(AdventError::parse(UNNAMED, i + 1, 1, id, &reason));
         }
         boxes.push(box_chars);
     }
+    if boxes.len() < 2 {
+        return Err(AdventError::no_answer("at least two box ids are required"));
+    }
 
     // search for boxes that are the closest match (same letters in same position)
     let box_count = boxes.len();
-    let char_count = boxes[0].len();
     let mut box_1 = 0;
     let mut box_2 = 0;
-    let mut max_diff = std::i32::MAX;
+    let mut max_diff = i32::MAX;
 
     for index_1 in 0..(box_count - 1) {
         for index_2 in (index_1 + 1)..box_count {
             let mut diff = 0;
-            for i in 0..char_count {
-                if boxes[index_1][i] != boxes[index_2][i] {
+            for (a, b) in boxes[index_1].iter().zip(&boxes[index_2]) {
+                if a != b {
                     diff += 1;
                 }
             }
@@ -72,11 +99,70 @@ pub fn part2(filename: &str) -> String {
     }
 
     let mut answer: Vec<char> = Vec::new();
-    for i in 0..char_count {
-        if boxes[box_1][i] == boxes[box_2][i] {
-            answer.push(boxes[box_1][i]);
+    for (a, b) in boxes[box_1].iter().zip(&boxes[box_2]) {
+        if a == b {
+            answer.push(*a);
         }
     }
 
-    answer.into_iter().collect()
+    Ok(answer.into_iter().collect())
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    fn day(&self) -> u32 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Inventory Management System"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day2-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^[a-z]+$", "a box id of lowercase letters")).with_check(check_ids)
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_example() {
+        let ids = "abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab";
+        assert_eq!(12, part1_from(ids.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        let ids = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz";
+        assert_eq!("fgij", part2_from(ids.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_different_lengths() {
+        assert!(part2_from("abcde\nabc".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn lint_ids() {
+        let problems: Vec<String> = Day2.grammar().lint("abcde\nabc\nAB1de\nfghij").iter().map(|e| e.to_string()).collect();
+        assert_eq!(vec![
+            "<input>:2:1: expected an id of 5 letters: 'abc'",
+            "<input>:3:1: expected a box id of lowercase letters: 'AB1de'",
+        ], problems);
+    }
 }
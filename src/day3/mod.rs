@@ -1,7 +1,12 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-use regex::Regex;
+use geometry::Point;
+use grid::Grid;
+use input;
+use lint::{self, Rule};
+use parse::{Fields, FromFields, LineParser};
+use render::{self, Image, Palette};
+use {AdventError, Grammar, Input, Result, Solution};
 
 const FABRIC_SIZE: usize = 1000;
 
@@ -19,79 +24,138 @@ struct Claim {
     size: Rect,
 }
 
-fn parse_claims(filename: &str) -> Vec<Claim> {
-    let mut claims: Vec<Claim> = Vec::new();
-    let file = File::open(filename).expect("file not found");
-    // line format: #1107 @ 509,248: 27x11
-    let claim_regex = Regex::new(r"#(\d+) @ (\d+),(\d+): (\d+)x(\d+)").expect("bad regex");
-    for line in BufReader::new(file).lines() {
-        let data = line.unwrap();
-        let caps = claim_regex.captures(&data).expect("line does not match regex");
+impl Claim {
+    /// The coordinates of every square inch of fabric covered by the claim.
+    fn squares(&self) -> impl Iterator<Item=Point> {
+        let (x, y, w, h) = (self.x as i32, self.y as i32, self.size.w as i32, self.size.h as i32);
+        (y..y + h).flat_map(move |y| (x..x + w).map(move |x| Point::new(x, y)))
+    }
+}
+
+impl FromFields for Claim {
+    fn from_fields(fields: &Fields) -> Result<Claim> {
         let claim = Claim {
-            id: caps[1].parse::<usize>().expect("not an int"),
-            x: caps[2].parse::<usize>().expect("not an int"),
-            y: caps[3].parse::<usize>().expect("not an int"),
+            id: fields.get(1)?,
+            x: fields.get(2)?,
+            y: fields.get(3)?,
             size: Rect {
-                w: caps[4].parse::<usize>().expect("not an int"),
-                h: caps[5].parse::<usize>().expect("not an int"),
+                w: fields.get(4)?,
+                h: fields.get(5)?,
             },
         };
-        claims.push(claim);
+        // checked so huge offsets are reported rather than wrapping around onto the fabric
+        let fits = |start: usize, length: usize| start.checked_add(length).is_some_and(|end| end <= FABRIC_SIZE);
+        if !fits(claim.x, claim.size.w) || !fits(claim.y, claim.size.h) {
+            return Err(fields.error("claim does not fit on the fabric"));
+        }
+        Ok(claim)
     }
-
-    claims
 }
 
-pub fn part1(filename: &str) -> usize {
-    let claims = parse_claims(filename);
-    let mut fabric = [0; FABRIC_SIZE * FABRIC_SIZE];
-    let mut total = 0;
+fn parse_claims<R: BufRead>(reader: R) -> Result<Vec<Claim>> {
+    // line format: #1107 @ 509,248: 27x11
+    LineParser::new(r"#(\d+) @ (\d+),(\d+): (\d+)x(\d+)").parse(reader)
+}
 
+/// Counts how many claims cover each square inch of fabric.
+fn claim_fabric(claims: &[Claim]) -> Grid<u32> {
+    let mut fabric = Grid::new(FABRIC_SIZE, FABRIC_SIZE, 0);
     for claim in claims {
-        for x in claim.x..(claim.x + claim.size.w) {
-            for y in claim.y..(claim.y + claim.size.h) {
-                let index = x + y * FABRIC_SIZE;
-                fabric[index] += 1;
-                if fabric[index] == 2 {
-                    total += 1;
-                }
-            }
+        for square in claim.squares() {
+            fabric[square] += 1;
         }
     }
-    total
+    fabric
 }
 
-pub fn part2(filename: &str) -> usize {
-    let claims = parse_claims(filename);
-    let mut fabric = [0; FABRIC_SIZE * FABRIC_SIZE];
+pub fn part1(filename: &str) -> Result<usize> {
+    input::with_file(filename, part1_from)
+}
 
-    // claim sections in fabric
-    for claim in &claims {
-        for x in claim.x..(claim.x + claim.size.w) {
-            for y in claim.y..(claim.y + claim.size.h) {
-                fabric[x + y * FABRIC_SIZE] += 1;
-            }
-        }
-    }
+pub fn part1_from<R: BufRead>(reader: R) -> Result<usize> {
+    let claims = parse_claims(reader)?;
+    let fabric = claim_fabric(&claims);
+    Ok(fabric.values().filter(|&&claimed| claimed >= 2).count())
+}
+
+pub fn part2(filename: &str) -> Result<usize> {
+    input::with_file(filename, part2_from)
+}
+
+pub fn part2_from<R: BufRead>(reader: R) -> Result<usize> {
+    let claims = parse_claims(reader)?;
+    let fabric = claim_fabric(&claims);
 
     // find claim that is only claimed once
     for claim in &claims {
-        let mut valid = true;
-
-        for x in claim.x..(claim.x + claim.size.w) {
-            for y in claim.y..(claim.y + claim.size.h) {
-                if fabric[x + y * FABRIC_SIZE] != 1 {
-                    valid = false;
-                    break;
-                }
-            }
-            if !valid {
-                break;
-            }
-        }
-        if valid {
-            return claim.id;
+        if claim.squares().all(|square| fabric[square] == 1) {
+            return Ok(claim.id);
         }
     }
-    unreachable!("result not found");
-}
\ No newline at end of file
+    Err(AdventError::no_answer("every claim overlaps another claim"))
+}
+
+/// A heatmap of the fabric, the more claims a square inch is in the brighter it is.
+pub fn render_from<R: BufRead>(reader: R) -> Result<Image> {
+    let fabric = claim_fabric(&parse_claims(reader)?);
+    Ok(Image::Pixmap(render::heatmap(&fabric, &Palette::heat(), |&claimed| claimed as f64)))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    fn day(&self) -> u32 {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "No Matter How You Slice It"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day3-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^#\d+ @ \d+,\d+: \d+x\d+$", "a claim such as '#1 @ 1,3: 4x4'"))
+            .with_check(|lines| lint::each_line(lines, |line| parse_claims(line.as_bytes())))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn render(&self, input: &str) -> Result<Image> {
+        render_from(input.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_CLAIMS: &str = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(4, part1_from(EXAMPLE_CLAIMS.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(3, part2_from(EXAMPLE_CLAIMS.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn claim_off_the_fabric() {
+        let e = part1_from("#1 @ 1,3: 4x4\n#2 @ 999,1: 4x4".as_bytes()).unwrap_err();
+        assert_eq!("<input>:2:1: claim does not fit on the fabric: '#2 @ 999,1: 4x4'", e.to_string());
+
+        let e = part1_from("#1 @ 1,18446744073709551615: 4x4".as_bytes()).unwrap_err();
+        assert_eq!("<input>:1:1: claim does not fit on the fabric: '#1 @ 1,18446744073709551615: 4x4'", e.to_string());
+    }
+}
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-use regex::Regex;
+use input::{self, UNNAMED};
+use lint::Rule;
+use parse::LineParser;
+use {AdventError, Grammar, Input, Result, Solution};
 
 struct Guard {
     id: usize,
@@ -27,39 +29,49 @@ File Format:
 [1518-11-23 00:52] falls asleep
 [1518-11-23 00:57] wakes up
 */
-fn parse_guard_data(filename: &str) -> HashMap<usize, Guard> {
-    // read all lines and then sort them
-    let mut lines: Vec<String> = Vec::new();
-    let file = File::open(filename).expect("file not found");
-    for line in BufReader::new(file).lines() {
-        lines.push(line.unwrap());
+fn parse_guard_data<R: BufRead>(reader: R) -> Result<HashMap<usize, Guard>> {
+    // read all lines and then sort them, keeping the line numbers for error reporting
+    let mut lines: Vec<(String, usize)> = Vec::new();
+    for (i, line) in input::lines(reader).enumerate() {
+        lines.push((line?, i + 1));
     }
     lines.sort();
 
-    let mut guard_id: usize = 0;
+    let mut guard_id: Option<usize> = None;
     let mut guards: HashMap<usize, Guard> = HashMap::new();
     let mut awake = true;
     let mut sleep_minute: usize = 0;
 
-    let guard_line = Regex::new(r"Guard #(\d+) begins").expect("bad regex");
+    let guard_line = LineParser::new(r"Guard #(\d+) begins");
 
-    for line in lines {
-        match guard_line.captures(&line) {
-            Some(caps) => {
-                guard_id = caps[1].parse::<usize>().expect("not an int");
-                guards.entry(guard_id).or_insert(Guard {
-                    id: guard_id,
+    for (line, line_number) in lines {
+        match guard_line.match_line(line_number, &line) {
+            Some(guard) => {
+                let (id,) = guard?;
+                guards.entry(id).or_insert(Guard {
+                    id,
                     minutes: [0; 60],
                     total_minutes: 0,
                 });
+                guard_id = Some(id);
+                awake = true;
             }
             None => {
-                let minute = line[15..17].parse::<usize>().expect("not an int");
+                let minute = match line.get(15..17).map(|m| m.parse::<usize>()) {
+                    Some(Ok(m)) if m < 60 => m,
+                    _ => return Err(AdventError::parse(UNNAMED, line_number, 16, &line, "expected a minute")),
+                };
+                let g = match guard_id {
+                    None => return Err(AdventError::parse(UNNAMED, line_number, 1, &line, "no guard on shift")),
+                    Some(id) => guards.get_mut(&id).unwrap(),
+                };
                 if awake {
                     sleep_minute = minute;
                     awake = false;
                 } else {
-                    let g = guards.get_mut(&guard_id).unwrap();
+                    if minute < sleep_minute {
+                        return Err(AdventError::parse(UNNAMED, line_number, 16, &line, "woke up before falling asleep"));
+                    }
                     g.add_time(sleep_minute, minute);
                     awake = true;
                 }
@@ -67,11 +79,46 @@ fn parse_guard_data(filename: &str) -> HashMap<usize, Guard> {
         }
     }
 
-    guards
+    Ok(guards)
+}
+
+/// Records are read in timestamp order, so they are checked in that order: every nap needs a
+/// guard on shift and must alternate between falling asleep and waking up.
+fn check_records(lines: &[(usize, &str)]) -> Vec<AdventError> {
+    let mut records = lines.to_vec();
+    records.sort_by_key(|&(_, line)| line);
+
+    let mut problems = Vec::new();
+    let mut on_shift = false;
+    let mut asleep = false;
+    for (line_number, line) in records {
+        if line.ends_with("begins shift") {
+            on_shift = true;
+            asleep = false;
+            continue;
+        }
+        let falls_asleep = line.ends_with("falls asleep");
+        let reason = if !on_shift {
+            "no guard on shift"
+        } else if falls_asleep && asleep {
+            "fell asleep while asleep"
+        } else if !falls_asleep && !asleep {
+            "woke up without falling asleep"
+        } else {
+            asleep = falls_asleep;
+            continue;
+        };
+        problems.push(AdventError::parse(UNNAMED, line_number, 1, line, reason));
+    }
+    problems
+}
+
+pub fn part1(filename: &str) -> Result<usize> {
+    input::with_file(filename, part1_from)
 }
 
-pub fn part1(filename: &str) -> usize {
-    let guards = parse_guard_data(filename);
+pub fn part1_from<R: BufRead>(reader: R) -> Result<usize> {
+    let guards = parse_guard_data(reader)?;
 
     let mut max_minutes: usize = 0;
 
@@ -85,7 +132,7 @@ pub fn part1(filename: &str) -> usize {
     }
 
     // find minute guard is asleep the most often
-    let guard = guards.get(&guard_id).expect("invalid guard id");
+    let guard = guards.get(&guard_id).ok_or_else(|| AdventError::no_answer("no guard fell asleep"))?;
     max_minutes = 0;
     let mut best_time: usize = 0;
     for i in 0..guard.minutes.len() {
@@ -94,11 +141,15 @@ pub fn part1(filename: &str) -> usize {
             best_time = i;
         }
     }
-    guard.id * best_time
+    Ok(guard.id * best_time)
+}
+
+pub fn part2(filename: &str) -> Result<usize> {
+    input::with_file(filename, part2_from)
 }
 
-pub fn part2(filename: &str) -> usize {
-    let guards = parse_guard_data(filename);
+pub fn part2_from<R: BufRead>(reader: R) -> Result<usize> {
+    let guards = parse_guard_data(reader)?;
     let mut guard_id = 0;
     let mut best_time: usize = 0;
     let mut max_minutes: usize = 0;
@@ -113,5 +164,90 @@ pub fn part2(filename: &str) -> usize {
         }
     }
 
-    guard_id * best_time
-}
\ No newline at end of file
+    Ok(guard_id * best_time)
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    fn day(&self) -> u32 {
+        4
+    }
+
+    fn title(&self) -> &'static str {
+        "Repose Record"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day4-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^\[\d{4}-\d\d-\d\d \d\d:[0-5]\d\] (Guard #\d+ begins shift|falls asleep|wakes up)$",
+                                  "a record such as '[1518-11-01 00:05] falls asleep'"))
+            .with_check(check_records)
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_RECORDS: &str = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(240, part1_from(EXAMPLE_RECORDS.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(4455, part2_from(EXAMPLE_RECORDS.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn no_guard_on_shift() {
+        let e = part1_from("[1518-11-01 00:05] falls asleep".as_bytes()).unwrap_err();
+        assert_eq!("<input>:1:1: no guard on shift: '[1518-11-01 00:05] falls asleep'", e.to_string());
+    }
+
+    #[test]
+    fn lint_records() {
+        assert!(Day4.grammar().lint(EXAMPLE_RECORDS).is_empty());
+
+        let records = "[1518-11-01 00:05] falls asleep
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] wakes up
+[1518-11-01 00:61] falls asleep";
+        let problems: Vec<String> = Day4.grammar().lint(records).iter().map(|e| e.to_string()).collect();
+        assert_eq!(vec![
+            "<input>:4:1: woke up without falling asleep: '[1518-11-01 00:30] wakes up'",
+            "<input>:5:1: expected a record such as '[1518-11-01 00:05] falls asleep': '[1518-11-01 00:61] falls asleep'",
+        ], problems);
+    }
+}
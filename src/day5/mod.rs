@@ -1,26 +1,31 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use rayon::prelude::*;
 
-fn parse_file(filename: &str) -> Vec<i32> {
-    // read all lines and then sort them
+use input::{self, UNNAMED};
+use lint::Rule;
+use {AdventError, Grammar, Input, Result, Solution};
+
+fn parse_file<R: BufRead>(reader: R) -> Result<Vec<i32>> {
     let mut chars: Vec<i32> = Vec::new();
-    let file = File::open(filename).expect("file not found");
-    for line in BufReader::new(file).lines() {
-        for c in line.unwrap().chars() {
+    for (i, line) in input::lines(reader).enumerate() {
+        let data = line?;
+        for (column, c) in data.chars().enumerate() {
+            if !c.is_ascii_alphabetic() {
+                return Err(AdventError::parse(UNNAMED, i + 1, column + 1, &c.to_string(), "not a unit"));
+            }
             chars.push(c as i32);
         }
     }
-    chars
+    Ok(chars)
 }
 
-fn react(input: &Vec<i32>, skip: i32) -> usize {
+fn react(input: &[i32], skip: i32) -> usize {
     let mut stack = Vec::with_capacity(input.len());
     for unit in input {
         if *unit == skip || *unit == skip + 32 {
             continue;
         }
-        if stack.len() == 0 {
+        if stack.is_empty() {
             stack.push(unit)
         } else if (stack[stack.len() - 1] - unit).abs() == 32 {
             stack.pop();
@@ -31,36 +36,91 @@ fn react(input: &Vec<i32>, skip: i32) -> usize {
     stack.len()
 }
 
-pub fn part1(filename: &str) -> usize {
-    let chars = parse_file(filename);
-    react(&chars, 0)
+pub fn part1(filename: &str) -> Result<usize> {
+    input::with_file(filename, part1_from)
+}
+
+pub fn part1_from<R: BufRead>(reader: R) -> Result<usize> {
+    let chars = parse_file(reader)?;
+    Ok(react(&chars, 0))
+}
+
+pub fn part2(filename: &str) -> Result<usize> {
+    input::with_file(filename, part2_from)
+}
+
+pub fn part2_from<R: BufRead>(reader: R) -> Result<usize> {
+    let data = parse_file(reader)?;
+    Ok((65..91).map(|c| react(&data, c)).min().unwrap())
 }
 
-pub fn part2(filename: &str) -> usize {
-    let data = parse_file(filename);
-    (65..91).map(|c| react(&data, c)).min().unwrap()
+pub fn part2_parallel(filename: &str) -> Result<usize> {
+    input::with_file(filename, part2_parallel_from)
 }
 
-pub fn part2_parallel(filename: &str) -> usize {
-    let data = parse_file(filename);
-    (65..91).into_par_iter().map(|c| react(&data, c)).min().unwrap()
+pub fn part2_parallel_from<R: BufRead>(reader: R) -> Result<usize> {
+    let data = parse_file(reader)?;
+    Ok((65..91).into_par_iter().map(|c| react(&data, c)).min().unwrap())
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    fn day(&self) -> u32 {
+        5
+    }
+
+    fn title(&self) -> &'static str {
+        "Alchemical Reduction"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day5-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::line(Rule::new(r"^[a-zA-Z]+$", "a polymer of letters"))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "nightly")]
     use test::Bencher;
 
+    #[test]
+    fn part1_example() {
+        assert_eq!(10, part1_from("dabAcCaCBAcCcaDA".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(4, part2_from("dabAcCaCBAcCcaDA".as_bytes()).unwrap());
+        assert_eq!(4, part2_parallel_from("dabAcCaCBAcCcaDA".as_bytes()).unwrap());
+    }
+
+    #[cfg(feature = "nightly")]
     #[bench]
     fn part1_bench(b: &mut Bencher) {
         b.iter(|| part1("data/day5-input.txt"));
     }
 
+    #[cfg(feature = "nightly")]
     #[bench]
     fn part2_bench(b: &mut Bencher) {
         b.iter(|| part2("data/day5-input.txt"));
     }
 
+    #[cfg(feature = "nightly")]
     #[bench]
     fn part2_parallel_bench(b: &mut Bencher) {
         b.iter(|| part2_parallel("data/day5-input.txt"));
@@ -1,87 +1,87 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-#[derive(Debug)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+use geometry::{BoundingBox, Point, Vector};
+use grid::Grid;
+use input::{self, UNNAMED};
+use lint::{self, Rule};
+use render::{Image, Palette, Rgb};
+use {AdventError, Grammar, Input, Result, Solution};
+
+/// The safe region is every location whose total distance to the coordinates is below this.
+const MAX_TOTAL_DISTANCE: i32 = 10000;
 
-fn parse_points(filename: &str) -> Vec<Point> {
-    // read all lines and then sort them
+fn parse_points<R: BufRead>(reader: R) -> Result<Vec<Point>> {
     let mut points: Vec<Point> = Vec::new();
-    let file = File::open(filename).expect("file not found");
-    for line in BufReader::new(file).lines() {
-        let data = line.unwrap();
-        let parts: Vec<&str> = data.split(", ").collect();
-        points.push(Point {
-            x: parts[0].parse::<i32>().expect("not an int"),
-            y: parts[1].parse::<i32>().expect("not an int"),
-        });
+    // line format: 181, 184
+    for (i, line) in input::lines(reader).enumerate() {
+        let data = line?;
+        let comma = data.find(", ")
+            .ok_or_else(|| AdventError::parse(UNNAMED, i + 1, 1, &data, "expected 'x, y'"))?;
+        let (x, y) = (&data[..comma], &data[comma + 2..]);
+        points.push(Point::new(
+            x.parse::<i32>().map_err(|_| AdventError::parse(UNNAMED, i + 1, 1, x, "not an int"))?,
+            y.parse::<i32>().map_err(|_| AdventError::parse(UNNAMED, i + 1, comma + 3, y, "not an int"))?,
+        ));
     }
 
-    points
+    if points.is_empty() {
+        return Err(AdventError::no_answer("no coordinates"));
+    }
+    Ok(points)
 }
 
-fn find_bounds(points: &Vec<Point>) -> (i32, i32, i32, i32) {
-    let mut min_x = points[0].x;
-    let mut min_y = points[0].y;
-    let mut max_x = points[0].x;
-    let mut max_y = points[0].y;
-    for p in points {
-        if p.x > max_x {
-            max_x = p.x;
-        }
-        if p.x < min_x {
-            min_x = p.x;
-        }
-        if p.y > max_y {
-            max_y = p.y;
-        }
-        if p.y < min_y {
-            min_y = p.y;
+/// Builds a grid covering the bounds whose cells are the result of `f` at each location.
+fn map_area<T, F>(bounds: &BoundingBox, f: F) -> Grid<T>
+    where F: Fn(Point) -> T {
+    let min = bounds.min;
+    Grid::from_fn(bounds.width(), bounds.height(), |p| f(min + Vector::new(p.x, p.y))).with_origin(min)
+}
+
+/// Returns the index of the point closest to `location`, unless several points are equally close.
+fn closest_point(points: &[Point], location: Point) -> Option<usize> {
+    let mut closest_distance = 100000;
+    let mut closest_point: usize = 1000000;
+    let mut closest_count = 0;
+
+    for (i, p) in points.iter().enumerate() {
+        let point_distance = p.manhattan(location);
+        if point_distance < closest_distance {
+            closest_distance = point_distance;
+            closest_point = i;
+            closest_count = 1;
+        } else if point_distance == closest_distance {
+            closest_count += 1;
         }
     }
-    (min_x, min_y, max_x, max_y)
+
+    if closest_count == 1 {
+        Some(closest_point)
+    } else {
+        None
+    }
 }
 
-fn calculate_manhattan(points: &Vec<Point>, min_x:i32, min_y:i32, max_x:i32, max_y:i32) -> Vec<usize> {
+fn calculate_manhattan(points: &[Point], bounds: &BoundingBox) -> Vec<usize> {
     let mut spots: Vec<usize> = vec!(0; points.len());
 
-    for x in min_x..(max_x + 1) {
-        for y in min_y..(max_y + 1) {
-            let mut closest_distance = 100000;
-            let mut closest_point: usize = 1000000;
-            let mut closest_count = 0;
-
-            for i in 0..points.len() {
-                let p = &points[i];
-
-                let point_distance = (p.x - x).abs() + (p.y - y).abs();
-                if point_distance < closest_distance {
-                    closest_distance = point_distance;
-                    closest_point = i;
-                    closest_count = 1;
-                } else if point_distance == closest_distance {
-                    closest_count += 1;
-                }
-            }
-
-            if closest_count == 1 {
-                spots[closest_point] += 1;
-            }
-        }
+    let closest = map_area(bounds, |location| closest_point(points, location));
+    for &i in closest.values().flatten() {
+        spots[i] += 1;
     }
 
     spots
 }
 
-pub fn part1(filename: &str) -> usize {
-    let points = parse_points(filename);
+pub fn part1(filename: &str) -> Result<usize> {
+    input::with_file(filename, part1_from)
+}
+
+pub fn part1_from<R: BufRead>(reader: R) -> Result<usize> {
+    let points = parse_points(reader)?;
 
-    let (min_x, min_y, max_x, max_y) = find_bounds(&points);
-    let spots = calculate_manhattan(&points, min_x, min_y, max_x, max_y);
-    let spots_larger = calculate_manhattan(&points, min_x+10, min_y+10, max_x+10, max_y+10);
+    let bounds = BoundingBox::from_points(points.iter().cloned()).expect("no points");
+    let spots = calculate_manhattan(&points, &bounds);
+    let spots_larger = calculate_manhattan(&points, &bounds.expand(10));
 
     let mut largest = 0;
     for i in 0..spots.len() {
@@ -94,29 +94,98 @@ pub fn part1(filename: &str) -> usize {
         }
     }
 
-    largest
+    Ok(largest)
+}
+
+pub fn part2(filename: &str) -> Result<usize> {
+    input::with_file(filename, part2_from)
 }
 
-pub fn part2(filename: &str) -> usize {
-    let points = parse_points(filename);
+pub fn part2_from<R: BufRead>(reader: R) -> Result<usize> {
+    safe_region_size(reader, MAX_TOTAL_DISTANCE)
+}
 
-    let (min_x, min_y, max_x, max_y) = find_bounds(&points);
+/// Counts the locations whose total distance to every coordinate is below `max_distance`.
+pub fn safe_region_size<R: BufRead>(reader: R, max_distance: i32) -> Result<usize> {
+    let points = parse_points(reader)?;
 
-    let mut region_size = 0;
+    let bounds = BoundingBox::from_points(points.iter().cloned()).expect("no points");
 
-    for x in min_x..(max_x + 1) {
-        for y in min_y..(max_y + 1) {
-            let mut total_distance = 0;
-            for i in 0..points.len() {
-                let p = &points[i];
-                let point_distance = (p.x - x).abs() + (p.y - y).abs();
-                total_distance += point_distance;
-            }
-            if total_distance < 10000 {
-                region_size += 1;
-            }
-        }
+    let total_distances = map_area(&bounds, |location| {
+        points.iter().map(|p| p.manhattan(location)).sum::<i32>()
+    });
+    let region_size = total_distances.values().filter(|&&d| d < max_distance).count();
+
+    Ok(region_size)
+}
+
+/// Colours every location after the coordinate closest to it, locations equally close to
+/// several coordinates are black.
+pub fn render_from<R: BufRead>(reader: R) -> Result<Image> {
+    let points = parse_points(reader)?;
+    let bounds = BoundingBox::from_points(points.iter().cloned()).expect("no points");
+
+    let palette = Palette::new(vec![Rgb(0, 0, 255), Rgb(0, 255, 0), Rgb(255, 255, 0), Rgb(255, 0, 0)]);
+    let last = (points.len() - 1).max(1) as f64;
+    let closest = map_area(&bounds, |location| match closest_point(&points, location) {
+        None => Rgb::BLACK,
+        Some(i) => palette.colour(i as f64 / last),
+    });
+    Ok(Image::Pixmap(closest))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    fn day(&self) -> u32 {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Coordinates"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day6-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^\d+, \d+$", "coordinates such as '1, 6'"))
+            .with_check(|lines| lint::each_line(lines, |line| parse_points(line.as_bytes())))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
     }
 
-    region_size
-}
\ No newline at end of file
+    fn render(&self, input: &str) -> Result<Image> {
+        render_from(input.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(17, part1_from(EXAMPLE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(16, safe_region_size(EXAMPLE.as_bytes(), 32).unwrap());
+    }
+
+    #[test]
+    fn malformed_coordinate() {
+        let e = part1_from("1, 1\n1, x".as_bytes()).unwrap_err();
+        assert_eq!("<input>:2:4: not an int: 'x'", e.to_string());
+    }
+}
@@ -1,131 +1,121 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::iter::FromIterator;
-
-use regex::Regex;
-
-const MAX_TIME: i32 = 2147483647;
-const NO_WORK: char = '.';
+use std::io::BufRead;
+
+use input;
+use lint::Rule;
+use parse::{Fields, FromFields, LineParser};
+use scheduler::{Graph, Schedule};
+use {Grammar, Input, Result, Solution};
+
+/// Every step takes this many seconds plus its position in the alphabet.
+const BASE_DURATION: u64 = 60;
+const WORKERS: usize = 5;
+
+/// One line of the input: `step` cannot begin until `before` is finished.
+struct Dependency {
+    before: char,
+    step: char,
+}
 
-#[derive(Debug)]
-struct Step {
-    id: char,
-    dependencies: Vec<char>,
-    time: i32,
+impl FromFields for Dependency {
+    fn from_fields(fields: &Fields) -> Result<Dependency> {
+        let letter = |n: usize| {
+            fields.map(n, "not a step letter", |s| s.parse::<char>().ok().filter(|c| c.is_ascii_uppercase()))
+        };
+        Ok(Dependency {
+            before: letter(1)?,
+            step: letter(2)?,
+        })
+    }
 }
 
-fn parse_dependencies(filename: &str) -> HashMap<char, Step> {
-    let mut steps: HashMap<char, Step> = HashMap::new();
-    let file = File::open(filename).expect("file not found");
+fn parse_dependencies<R: BufRead>(reader: R, base_duration: u64) -> Result<Graph<char>> {
+    let mut steps = Graph::new();
     // Format: Step G must be finished before step L can begin.
-    let claim_regex = Regex::new(r"Step (\w) must be finished before step (\w) can begin.").expect("bad regex");
-    for line in BufReader::new(file).lines() {
-        let data = line.unwrap();
-        let caps = claim_regex.captures(&data).expect("line does not match regex");
-        let step_before = caps[1].parse::<char>().expect("not a char");
-        let step = caps[2].parse::<char>().expect("not a char");
-
-        // ensure the before step exists
-        steps.entry(step_before).or_insert(Step {
-            id: step_before,
-            dependencies: Vec::new(),
-            time: (step_before as i32 - 64) + 60,
-        });
-
-        // add the before step to the specified step
-        let dependency = steps.entry(step).or_insert(Step {
-            id: step,
-            dependencies: Vec::new(),
-            time: (step as i32 - 64) + 60,
-        });
-        dependency.dependencies.push(step_before);
+    let parser = LineParser::new(r"Step (\w) must be finished before step (\w) can begin.");
+    for Dependency { before, step } in parser.parse(reader)? {
+        for &id in &[before, step] {
+            steps.add_task(id, base_duration + (id as u64 - 'A' as u64 + 1));
+        }
+        steps.add_dependency(before, step);
     }
 
-    steps
+    Ok(steps)
 }
 
-fn remove_step(steps: &mut HashMap<char, Step>, remove: char) {
-    for step in steps.values_mut() {
-        let result = step.dependencies.iter().position(|c| *c == remove);
-        match result {
-            Some(i) => {
-                step.dependencies.remove(i);
-                ()
-            }
-            None => ()
-        }
-    }
+/// Works through the steps with `workers` elves, available steps are taken alphabetically.
+fn assemble<R: BufRead>(reader: R, workers: usize, base_duration: u64) -> Result<Schedule<char>> {
+    parse_dependencies(reader, base_duration)?.schedule(workers, |a, b| a.cmp(b))
 }
 
-pub fn part1(filename: &str) -> String {
-    let mut steps = parse_dependencies(filename);
-    let mut order: Vec<char> = Vec::new();
+pub fn part1(filename: &str) -> Result<String> {
+    input::with_file(filename, part1_from)
+}
 
-    while steps.len() > 0 {
-        let mut valid_next: Vec<char> = Vec::new();
+pub fn part1_from<R: BufRead>(reader: R) -> Result<String> {
+    // a single worker finishes each step before picking the next, so it works in step order
+    let schedule = assemble(reader, 1, BASE_DURATION)?;
+    Ok(schedule.order().collect())
+}
 
-        for step in steps.values() {
-            if step.dependencies.len() == 0 {
-                valid_next.push(step.id);
-            }
-        }
-        valid_next.sort();
+pub fn part2(filename: &str) -> Result<u64> {
+    input::with_file(filename, part2_from)
+}
+
+pub fn part2_from<R: BufRead>(reader: R) -> Result<u64> {
+    let schedule = assemble(reader, WORKERS, BASE_DURATION)?;
+    Ok(schedule.end())
+}
+
+pub struct Day7;
 
-        order.push(valid_next[0]);
-        remove_step(&mut steps, valid_next[0]);
-        steps.remove(&valid_next[0]);
+impl Solution for Day7 {
+    fn day(&self) -> u32 {
+        7
     }
 
-    String::from_iter(order)
-}
+    fn title(&self) -> &'static str {
+        "The Sum of Its Parts"
+    }
 
-pub fn part2(filename: &str) -> i32 {
-    let mut steps = parse_dependencies(filename);
-    let mut worker_complete_time = [0; 5];
-    let mut worker_step = [NO_WORK; 5];
-    let mut now = 0;
-    while steps.len() > 0 {
-        // check if any steps available
-        let mut valid_next: Vec<char> = Vec::new();
-        for step in steps.values() {
-            if step.dependencies.len() == 0 && !worker_step.contains(&step.id) {
-                valid_next.push(step.id);
-            }
-        }
-        valid_next.sort();
-
-        // attempt to assign work to non-busy workers
-        for i in 0..worker_step.len() {
-            if valid_next.len() > 0 && worker_step[i] == NO_WORK {
-                let c = valid_next.remove(0);
-                let step = steps.get(&c).unwrap();
-                worker_complete_time[i] = now + step.time;
-                worker_step[i] = c;
-            }
-        }
+    fn input(&self) -> Input {
+        Input::File("data/day7-input.txt")
+    }
 
-        // find the next worker that will complete their work
-        let mut next_time = MAX_TIME;
-        let mut next_complete_worker = 0;
-        for i in 0..worker_step.len() {
-            if worker_step[i] == NO_WORK {
-                continue;
-            }
-            if worker_complete_time[i] < next_time {
-                next_time = worker_complete_time[i];
-                next_complete_worker = i;
-            }
-        }
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^Step [A-Z] must be finished before step [A-Z] can begin\.$",
+                                  "a step such as 'Step C must be finished before step A can begin.'"))
+    }
 
-        // adjust time
-        now = worker_complete_time[next_complete_worker];
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
+    }
+}
 
-        // complete workers step
-        remove_step(&mut steps, worker_step[next_complete_worker]);
-        steps.remove(&worker_step[next_complete_worker]);
-        worker_step[next_complete_worker] = NO_WORK;
-        worker_complete_time[next_complete_worker] = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!("CABDFE", part1_from(EXAMPLE.as_bytes()).unwrap());
     }
-    now
-}
\ No newline at end of file
+
+    #[test]
+    fn part2_example() {
+        let schedule = assemble(EXAMPLE.as_bytes(), 2, 0).unwrap();
+        assert_eq!(15, schedule.end());
+        assert_eq!("CAFBDE", schedule.order().collect::<String>());
+    }
+}
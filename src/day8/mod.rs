@@ -1,31 +1,56 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::str::SplitWhitespace;
 
+use input::{self, UNNAMED};
+use lint::{self, Rule};
+use {AdventError, Grammar, Input, Result, Solution};
+
 #[derive(Debug)]
 struct Node {
     children: Vec<Node>,
     metadata: Vec<usize>,
 }
 
-fn parse_node(data: &mut SplitWhitespace) -> Node {
-    let children_count: usize = data.next().unwrap().parse::<usize>().expect("not an int");
-    let metadata_count: usize = data.next().unwrap().parse::<usize>().expect("not an int");
+/// Reads the numbers of the single line tree definition, tracking their columns for errors.
+struct Numbers<'a> {
+    line: &'a str,
+    parts: SplitWhitespace<'a>,
+}
 
-    Node {
-        children: (0..children_count).map(|_| parse_node(data)).collect(),
-        metadata: (0..metadata_count).map(|_| data.next().unwrap().parse::<usize>().expect("not an int")).collect(),
+impl<'a> Numbers<'a> {
+    fn next(&mut self) -> Result<usize> {
+        match self.parts.next() {
+            None => Err(AdventError::parse(UNNAMED, 1, self.line.len() + 1, "", "unexpected end of input")),
+            Some(part) => {
+                let column = part.as_ptr() as usize - self.line.as_ptr() as usize + 1;
+                part.parse::<usize>()
+                    .map_err(|_| AdventError::parse(UNNAMED, 1, column, part, "not an int"))
+            }
+        }
     }
 }
 
-fn parse_tree(filename: &str) -> Node {
-    let file = File::open(filename).expect("file not found");
-    for line in BufReader::new(file).lines() {
-        let mut data = line.unwrap();
-        let mut parts = data.split_whitespace();
-        return parse_node(&mut parts);
-    }
-    unreachable!("failed to parse node");
+fn parse_node(data: &mut Numbers) -> Result<Node> {
+    let children_count: usize = data.next()?;
+    let metadata_count: usize = data.next()?;
+
+    Ok(Node {
+        children: (0..children_count).map(|_| parse_node(data)).collect::<Result<_>>()?,
+        metadata: (0..metadata_count).map(|_| data.next()).collect::<Result<_>>()?,
+    })
+}
+
+fn parse_tree<R: BufRead>(reader: R) -> Result<Node> {
+    let data = match input::lines(reader).next() {
+        None => String::new(),
+        Some(line) => line?,
+    };
+    let line = data.trim_end();
+    let mut numbers = Numbers {
+        line,
+        parts: line.split_whitespace(),
+    };
+    parse_node(&mut numbers)
 }
 
 fn sum_metadata(node: &Node) -> usize {
@@ -34,13 +59,17 @@ fn sum_metadata(node: &Node) -> usize {
     children_total + metadata_total
 }
 
-pub fn part1(filename: &str) -> usize {
-    let node = parse_tree(filename);
-    sum_metadata(&node)
+pub fn part1(filename: &str) -> Result<usize> {
+    input::with_file(filename, part1_from)
+}
+
+pub fn part1_from<R: BufRead>(reader: R) -> Result<usize> {
+    let node = parse_tree(reader)?;
+    Ok(sum_metadata(&node))
 }
 
 fn sum_metadata_part2(node: &Node) -> usize {
-    if node.children.len() == 0 {
+    if node.children.is_empty() {
         node.metadata.iter().sum()
     } else {
         let mut total: usize = 0;
@@ -54,22 +83,76 @@ fn sum_metadata_part2(node: &Node) -> usize {
     }
 }
 
-pub fn part2(filename: &str) -> usize {
-    let node = parse_tree(filename);
-    sum_metadata_part2(&node)
+pub fn part2(filename: &str) -> Result<usize> {
+    input::with_file(filename, part2_from)
+}
+
+pub fn part2_from<R: BufRead>(reader: R) -> Result<usize> {
+    let node = parse_tree(reader)?;
+    Ok(sum_metadata_part2(&node))
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    fn day(&self) -> u32 {
+        8
+    }
+
+    fn title(&self) -> &'static str {
+        "Memory Maneuver"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day8-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::line(Rule::new(r"^\d+( \d+)*$", "numbers separated by spaces"))
+            .with_check(|lines| lint::each_line(lines, |line| parse_tree(line.as_bytes())))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "nightly")]
     use test::Bencher;
 
     use super::*;
 
+    const EXAMPLE_TREE: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(138, part1_from(EXAMPLE_TREE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(66, part2_from(EXAMPLE_TREE.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn truncated_tree() {
+        let e = part1_from("2 3 0 3 10".as_bytes()).unwrap_err();
+        assert_eq!("<input>:1:11: unexpected end of input: ''", e.to_string());
+    }
+
+    #[cfg(feature = "nightly")]
     #[bench]
     fn part1_bench(b: &mut Bencher) {
         b.iter(|| part1("data/day8-input.txt"));
     }
 
+    #[cfg(feature = "nightly")]
     #[bench]
     fn part2_bench(b: &mut Bencher) {
         b.iter(|| part2("data/day8-input.txt"));
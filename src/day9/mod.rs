@@ -1,100 +1,122 @@
-struct Node {
-    value: usize,
-    next: usize,
-    previous: usize,
-}
-
-struct CircularLinkedList {
-    free: Vec<usize>,
-    used: Vec<Node>,
-    current_index: usize,
-}
-
-impl CircularLinkedList {
-    fn new(initial: usize, capacity: usize) -> CircularLinkedList {
-        let mut values = Vec::with_capacity(capacity);
-        values.push(Node {
-            value: initial,
-            previous: 0,
-            next: 0,
-        });
-        CircularLinkedList {
-            free: Vec::new(),
-            used: values,
-            current_index: 0,
-        }
-    }
+use std::io::BufRead;
 
-    fn get_free_node(&mut self) -> usize {
-        match self.free.pop() {
-            None => {
-                // no free nodes, add one
-                self.used.push(Node {
-                    value: 0,
-                    previous: 0,
-                    next: 0,
-                });
-                self.used.len() - 1
-            }
-            Some(i) => {
-                i
-            }
-        }
-    }
+use regex::Regex;
 
-    fn insert(&mut self, value: usize) {
-        let current_index = self.current_index;
-        let old_next = self.used[current_index].next;
+use circular::CircularList;
+use input::{self, UNNAMED};
+use lint::{self, Rule};
+use simulation::{Driver, Simulation};
+use {AdventError, Grammar, Input, Result, Solution};
 
-        let new_index = self.get_free_node();
+/// The most players a game may have, each one is given a score.
+const MAX_PLAYERS: usize = 10_000;
 
-        // update previous node to point to new node
-        self.used[current_index].next = new_index;
+/// The largest last marble a game may have. Part 2 plays a hundred times as many marbles and the
+/// board holds most of them, puzzle inputs stay well under this.
+const MAX_LAST_MARBLE: usize = 1_000_000;
 
-        // add new node
-        self.used[new_index].previous = current_index;
-        self.used[new_index].next = old_next;
-        self.used[new_index].value = value;
+struct Game {
+    players: usize,
+    last_marble: usize,
+}
 
-        // update next node to point to new node
-        self.used[old_next].previous = new_index;
+/// A game in progress, each step places the next marble.
+struct Marbles {
+    board: CircularList<usize>,
+    scores: Vec<usize>,
+    next_marble: usize,
+    last_marble: usize,
+}
 
-        self.current_index = new_index;
+impl Marbles {
+    fn new(players: usize, last_marble: usize) -> Marbles {
+        let mut board = CircularList::with_capacity(last_marble + 1);
+        board.insert_after(0);
+        Marbles {
+            board,
+            scores: vec![0; players],
+            next_marble: 1,
+            last_marble,
+        }
     }
+}
 
-    fn remove(&mut self) -> usize {
-        let current_index = self.current_index;
-        let previous = self.used[current_index].previous;
-        let next = self.used[current_index].next;
-        let value = self.used[current_index].value;
-        self.used[previous].next = next;
-        self.used[next].previous = previous;
-        self.free.push(current_index);
-
-        self.current_index = next;
+impl Simulation for Marbles {
+    /// The score of every player.
+    type State = Vec<usize>;
 
-        value
+    fn step(&mut self) {
+        let m = self.next_marble;
+        if m.is_multiple_of(23) {
+            self.board.move_by(-7);
+            let players = self.scores.len();
+            self.scores[(m - 1) % players] += m + self.board.remove().expect("the board is never empty");
+        } else {
+            self.board.move_by(1);
+            self.board.insert_after(m);
+        }
+        self.next_marble += 1;
     }
 
-    fn forward(&mut self, count: usize) {
-        for _ in 0..count {
-            self.current_index = self.used[self.current_index].next;
-        }
+    fn snapshot(&self) -> Vec<usize> {
+        self.scores.clone()
     }
 
-    fn back(&mut self, count: usize) {
-        for _ in 0..count {
-            self.current_index = self.used[self.current_index].previous;
-        }
+    fn is_finished(&self) -> bool {
+        self.next_marble > self.last_marble
     }
 }
 
-pub fn part1() -> usize {
-    calculate_score_fast(459, 71790)
+fn parse_game<R: BufRead>(reader: R) -> Result<Game> {
+    // file format: 459 players; last marble is worth 71790 points
+    let regex = Regex::new(r"^(\d+) players; last marble is worth (\d+) points$").expect("bad regex");
+    let data = match input::lines(reader).next() {
+        None => String::new(),
+        Some(line) => line?,
+    };
+    let caps = regex.captures(&data)
+        .ok_or_else(|| AdventError::parse(UNNAMED, 1, 1, &data, "line does not match regex"))?;
+    let field = |n: usize| {
+        let m = caps.get(n).unwrap();
+        m.as_str().parse::<usize>()
+            .map_err(|_| AdventError::parse(UNNAMED, 1, m.start() + 1, m.as_str(), "not an int"))
+    };
+    let game = Game {
+        players: field(1)?,
+        last_marble: field(2)?,
+    };
+    if game.players == 0 {
+        return Err(AdventError::parse(UNNAMED, 1, 1, &data, "at least one player is required"));
+    }
+    if game.players > MAX_PLAYERS {
+        let reason = format!("at most {} players are allowed", MAX_PLAYERS);
+        return Err(AdventError::parse(UNNAMED, 1, 1, &data, &reason));
+    }
+    if game.last_marble > MAX_LAST_MARBLE {
+        let reason = format!("the last marble is worth at most {} points", MAX_LAST_MARBLE);
+        return Err(AdventError::parse(UNNAMED, 1, 1, &data, &reason));
+    }
+    Ok(game)
+}
+
+pub fn part1(filename: &str) -> Result<usize> {
+    input::with_file(filename, part1_from)
+}
+
+pub fn part1_from<R: BufRead>(reader: R) -> Result<usize> {
+    let game = parse_game(reader)?;
+    calculate_score_fast(game.players, game.last_marble)
 }
 
-pub fn part2() -> usize {
-    calculate_score_fast(459, 71790 * 100)
+pub fn part2(filename: &str) -> Result<usize> {
+    input::with_file(filename, part2_from)
+}
+
+pub fn part2_from<R: BufRead>(reader: R) -> Result<usize> {
+    let game = parse_game(reader)?;
+    let last_marble = game.last_marble.checked_mul(100)
+        .ok_or_else(|| AdventError::no_answer("the last marble of part 2 is too large"))?;
+    calculate_score_fast(game.players, last_marble)
 }
 
 #[allow(dead_code)]
@@ -118,66 +140,105 @@ fn calculate_score(players: usize, last_marble: usize) -> usize {
     *player_scores.iter().max().unwrap()
 }
 
-fn calculate_score_fast(players: usize, last_marble: usize) -> usize {
-    let mut player_scores: Vec<usize> = vec![0; players];
-    let mut board = CircularLinkedList::new(0, last_marble);
+fn calculate_score_fast(players: usize, last_marble: usize) -> Result<usize> {
+    let mut game = Driver::new(Marbles::new(players, last_marble));
+    game.run()?;
 
-    for m in 1..=last_marble {
-        if m % 23 == 0 {
-            board.back(7);
-            player_scores[(m - 1) % players] += m + board.remove();
-        } else {
-            board.forward(1);
-            board.insert(m);
-        }
+    Ok(*game.snapshot().iter().max().unwrap())
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    fn day(&self) -> u32 {
+        9
     }
 
-    *player_scores.iter().max().unwrap()
+    fn title(&self) -> &'static str {
+        "Marble Mania"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day9-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::line(Rule::new(r"^\d+ players; last marble is worth \d+ points$",
+                                 "a game such as '10 players; last marble is worth 1618 points'"))
+            .with_check(|lines| lint::each_line(lines, |line| parse_game(line.as_bytes())))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "nightly")]
     use test::Bencher;
 
     use super::*;
 
+    #[cfg(feature = "nightly")]
     #[bench]
     fn part1_bench(b: &mut Bencher) {
-        b.iter(|| part1());
+        b.iter(|| part1("data/day9-input.txt"));
     }
 
+    #[cfg(feature = "nightly")]
     #[bench]
     fn part2_bench(b: &mut Bencher) {
-        b.iter(|| part2());
+        b.iter(|| part2("data/day9-input.txt"));
+    }
+
+    #[test]
+    fn part1_from_example() {
+        let game = "10 players; last marble is worth 1618 points";
+        assert_eq!(8317, part1_from(game.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn oversized_games() {
+        let e = part2_from("10 players; last marble is worth 18446744073709551615 points".as_bytes()).unwrap_err();
+        assert_eq!("<input>:1:1: the last marble is worth at most 1000000 points: \
+                    '10 players; last marble is worth 18446744073709551615 points'", e.to_string());
+        let e = part1_from("100000 players; last marble is worth 25 points".as_bytes()).unwrap_err();
+        assert_eq!("<input>:1:1: at most 10000 players are allowed: '100000 players; last marble is worth 25 points'",
+                   e.to_string());
     }
 
     #[test]
     fn part1_example1() {
-        assert_eq!(32, calculate_score_fast(9, 25));
+        assert_eq!(32, calculate_score_fast(9, 25).unwrap());
     }
 
     #[test]
     fn part1_example2() {
-        assert_eq!(8317, calculate_score_fast(10, 1618));
+        assert_eq!(8317, calculate_score_fast(10, 1618).unwrap());
     }
 
     #[test]
     fn part1_example3() {
-        assert_eq!(146373, calculate_score_fast(13, 7999));
+        assert_eq!(146373, calculate_score_fast(13, 7999).unwrap());
     }
 
     #[test]
     fn part1_example4() {
-        assert_eq!(2764, calculate_score_fast(17, 1104));
+        assert_eq!(2764, calculate_score_fast(17, 1104).unwrap());
     }
 
     #[test]
     fn part1_example5() {
-        assert_eq!(54718, calculate_score_fast(21, 6111));
+        assert_eq!(54718, calculate_score_fast(21, 6111).unwrap());
     }
 
     #[test]
     fn part1_example6() {
-        assert_eq!(37305, calculate_score_fast(30, 5807));
+        assert_eq!(37305, calculate_score_fast(30, 5807).unwrap());
     }
 }
@@ -0,0 +1,113 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::result;
+use std::time::Duration;
+
+pub type Result<T> = result::Result<T, AdventError>;
+
+#[derive(Debug)]
+pub enum AdventError {
+    /// The input could not be read.
+    Io {
+        filename: String,
+        error: io::Error,
+    },
+    /// A line of the input is malformed, line and column are 1-based.
+    Parse {
+        filename: String,
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    /// The input was read but the puzzle has no answer for it.
+    NoAnswer(String),
+    /// The solution stopped because its cancellation token was cancelled.
+    Cancelled,
+    /// The solution did not finish within its time budget.
+    Timeout(Duration),
+}
+
+impl AdventError {
+    pub fn io(filename: &str, error: io::Error) -> AdventError {
+        AdventError::Io {
+            filename: filename.to_string(),
+            error,
+        }
+    }
+
+    pub fn parse(filename: &str, line: usize, column: usize, text: &str, reason: &str) -> AdventError {
+        AdventError::Parse {
+            filename: filename.to_string(),
+            line,
+            column,
+            text: text.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn no_answer(reason: &str) -> AdventError {
+        AdventError::NoAnswer(reason.to_string())
+    }
+
+    /// Names the input an error came from, used once the input is known to be a file.
+    pub fn with_filename(self, name: &str) -> AdventError {
+        match self {
+            AdventError::Io { error, .. } => AdventError::Io { filename: name.to_string(), error },
+            AdventError::Parse { line, column, text, reason, .. } => AdventError::Parse {
+                filename: name.to_string(),
+                line,
+                column,
+                text,
+                reason,
+            },
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for AdventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AdventError::Io { ref filename, ref error } => write!(f, "{}: {}", filename, error),
+            AdventError::Parse { ref filename, line, column, ref text, ref reason } => {
+                write!(f, "{}:{}:{}: {}: '{}'", filename, line, column, reason, text)
+            }
+            AdventError::NoAnswer(ref reason) => write!(f, "no answer: {}", reason),
+            AdventError::Cancelled => write!(f, "cancelled"),
+            AdventError::Timeout(budget) => write!(f, "TIMEOUT after {:#?}", budget),
+        }
+    }
+}
+
+impl Error for AdventError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            AdventError::Io { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_parse_error() {
+        let e = AdventError::parse("data/day3-input.txt", 12, 5, "x1", "not an int");
+        assert_eq!("data/day3-input.txt:12:5: not an int: 'x1'", e.to_string());
+    }
+
+    #[test]
+    fn with_filename() {
+        let e = AdventError::parse("<input>", 1, 1, "x", "not an int").with_filename("other.txt");
+        assert_eq!("other.txt:1:1: not an int: 'x'", e.to_string());
+    }
+
+    #[test]
+    fn display_timeout() {
+        assert_eq!("TIMEOUT after 1.5s", AdventError::Timeout(Duration::from_millis(1500)).to_string());
+    }
+}
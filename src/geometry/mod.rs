@@ -0,0 +1,253 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position on the integer plane, `y` grows downwards like the puzzle inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A displacement between two points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Vector {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    /// The distance moving only horizontally and vertically.
+    pub fn manhattan(self, other: Point) -> i32 {
+        (other - self).manhattan()
+    }
+
+    /// The distance moving in any of the eight directions, diagonal steps count as one.
+    pub fn chebyshev(self, other: Point) -> i32 {
+        (other - self).chebyshev()
+    }
+
+    /// The straight line distance.
+    pub fn euclidean(self, other: Point) -> f64 {
+        (other - self).length()
+    }
+}
+
+impl Vector {
+    pub fn new(x: i32, y: i32) -> Vector {
+        Vector { x, y }
+    }
+
+    pub fn manhattan(self) -> i32 {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn chebyshev(self) -> i32 {
+        self.x.abs().max(self.y.abs())
+    }
+
+    pub fn length(self) -> f64 {
+        (self.x as f64).hypot(self.y as f64)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, v: Vector) -> Point {
+        Point::new(self.x + v.x, self.y + v.y)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, v: Vector) -> Point {
+        Point::new(self.x - v.x, self.y - v.y)
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, v: Vector) {
+        *self = *self - v;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i32> for Vector {
+    type Output = Vector;
+
+    fn mul(self, n: i32) -> Vector {
+        Vector::new(self.x * n, self.y * n)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+/// The smallest rectangle containing a set of points, both corners are inside the box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    /// A box from `min` to `max` inclusive, the corners may be given in any order.
+    pub fn new(a: Point, b: Point) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// The box around every point, or `None` when there are no points.
+    pub fn from_points<I>(points: I) -> Option<BoundingBox>
+        where I: IntoIterator<Item=Point> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(BoundingBox::new(first, first), |b, p| b.union(&BoundingBox::new(p, p))))
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// The number of columns in the box.
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x) as usize + 1
+    }
+
+    /// The number of rows in the box.
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y) as usize + 1
+    }
+
+    /// The number of points inside the box.
+    pub fn area(&self) -> u64 {
+        self.width() as u64 * self.height() as u64
+    }
+
+    /// Grows the box by `n` on every side, a negative `n` shrinks it.
+    pub fn expand(&self, n: i32) -> BoundingBox {
+        let v = Vector::new(n, n);
+        BoundingBox::new(self.min - v, self.max + v)
+    }
+
+    /// Every point inside the box, row by row.
+    pub fn points(&self) -> impl Iterator<Item=Point> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+/// Moves the box without changing its size.
+impl Add<Vector> for BoundingBox {
+    type Output = BoundingBox;
+
+    fn add(self, v: Vector) -> BoundingBox {
+        BoundingBox { min: self.min + v, max: self.max + v }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let p = Point::new(1, 2);
+        let v = Vector::new(3, -4);
+        assert_eq!(Point::new(4, -2), p + v);
+        assert_eq!(Point::new(-2, 6), p - v);
+        assert_eq!(v, (p + v) - p);
+        assert_eq!(Vector::new(9, -12), v * 3);
+        assert_eq!(Vector::new(0, 0), v + -v);
+        assert_eq!(Vector::new(6, -8), v - -v);
+
+        let mut q = p;
+        q += v;
+        q -= v * 2;
+        assert_eq!(Point::new(-2, 6), q);
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point::new(1, 1);
+        let b = Point::new(4, 5);
+        assert_eq!(7, a.manhattan(b));
+        assert_eq!(4, a.chebyshev(b));
+        assert_eq!(5.0, a.euclidean(b));
+        assert_eq!(0, b.manhattan(b));
+    }
+
+    #[test]
+    fn bounding_box() {
+        let points = vec![Point::new(1, 1), Point::new(8, 3), Point::new(3, 9), Point::new(-2, 4)];
+        let b = BoundingBox::from_points(points).unwrap();
+        assert_eq!(BoundingBox::new(Point::new(-2, 1), Point::new(8, 9)), b);
+        assert_eq!((11, 9), (b.width(), b.height()));
+        assert_eq!(99, b.area());
+        assert!(b.contains(Point::new(8, 9)));
+        assert!(!b.contains(Point::new(9, 9)));
+        assert_eq!(None, BoundingBox::from_points(Vec::new()));
+
+        let single = BoundingBox::new(Point::new(2, 2), Point::new(2, 2));
+        assert_eq!(1, single.area());
+        assert_eq!(BoundingBox::new(Point::new(1, 1), Point::new(3, 3)), single.expand(1));
+        assert_eq!(vec![Point::new(2, 2)], single.points().collect::<Vec<_>>());
+        assert_eq!(9, single.expand(1).points().count());
+    }
+
+    #[test]
+    fn union_and_translate() {
+        let a = BoundingBox::new(Point::new(0, 0), Point::new(2, 2));
+        let b = BoundingBox::new(Point::new(5, -1), Point::new(6, 1));
+        assert_eq!(BoundingBox::new(Point::new(0, -1), Point::new(6, 2)), a.union(&b));
+        assert_eq!(BoundingBox::new(Point::new(10, 10), Point::new(12, 12)), a + Vector::new(10, 10));
+    }
+}
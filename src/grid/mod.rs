@@ -0,0 +1,272 @@
+use std::ops::{Index, IndexMut};
+
+use geometry::{BoundingBox, Point, Vector};
+
+/// Offsets of the four orthogonal neighbours of a cell.
+const ORTHOGONAL: [Vector; 4] = [
+    Vector { x: 0, y: -1 }, Vector { x: -1, y: 0 }, Vector { x: 1, y: 0 }, Vector { x: 0, y: 1 },
+];
+
+/// Offsets of all eight neighbours of a cell, in row order.
+const SURROUNDING: [Vector; 8] = [
+    Vector { x: -1, y: -1 }, Vector { x: 0, y: -1 }, Vector { x: 1, y: -1 },
+    Vector { x: -1, y: 0 }, Vector { x: 1, y: 0 },
+    Vector { x: -1, y: 1 }, Vector { x: 0, y: 1 }, Vector { x: 1, y: 1 },
+];
+
+/// A rectangular grid of cells stored row by row.
+///
+/// Cells are addressed by `Point`s, the top left cell is at the grid's origin which is `(0, 0)`
+/// unless moved with `with_origin`. `y` grows downwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    min_x: i32,
+    min_y: i32,
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            min_x: 0,
+            min_y: 0,
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid whose cells are the result of calling `f` with each cell's position.
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Grid<T>
+        where F: FnMut(Point) -> T {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                cells.push(f(Point::new(x, y)));
+            }
+        }
+        Grid { min_x: 0, min_y: 0, width, height, cells }
+    }
+
+    /// Moves the grid so its top left cell is at `origin`, the cells are unchanged.
+    pub fn with_origin(self, origin: Point) -> Grid<T> {
+        Grid { min_x: origin.x, min_y: origin.y, ..self }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The top left cell.
+    pub fn min(&self) -> Point {
+        Point::new(self.min_x, self.min_y)
+    }
+
+    /// The bottom right cell.
+    pub fn max(&self) -> Point {
+        Point::new(self.min_x + self.width as i32 - 1, self.min_y + self.height as i32 - 1)
+    }
+
+    /// The box from the top left to the bottom right cell, `None` for a grid without cells.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        if self.cells.is_empty() {
+            None
+        } else {
+            Some(BoundingBox::new(self.min(), self.max()))
+        }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.index(p.x, p.y).is_some()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index(p.x, p.y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        match self.index(p.x, p.y) {
+            None => None,
+            Some(i) => Some(&mut self.cells[i]),
+        }
+    }
+
+    /// The cells of row `y`, from left to right.
+    pub fn row(&self, y: i32) -> Option<&[T]> {
+        self.index(self.min_x, y).map(|start| &self.cells[start..start + self.width])
+    }
+
+    /// The cells of column `x`, from top to bottom.
+    pub fn column(&self, x: i32) -> Option<impl Iterator<Item=&T>> {
+        self.index(x, self.min_y).map(|start| self.cells[start..].iter().step_by(self.width))
+    }
+
+    /// Every row, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+        // chunks panics on a size of zero, an empty grid has no cells to split anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item=(Point, &T)> {
+        let (min_x, min_y, width) = (self.min_x, self.min_y, self.width);
+        self.cells.iter().enumerate().map(move |(i, cell)| {
+            (Point::new(min_x + (i % width) as i32, min_y + (i / width) as i32), cell)
+        })
+    }
+
+    /// Every cell, row by row.
+    pub fn values(&self) -> impl Iterator<Item=&T> {
+        self.cells.iter()
+    }
+
+    /// A grid of the same size and origin whose cells are the result of `f` on each cell.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+        where F: FnMut(&T) -> U {
+        Grid {
+            min_x: self.min_x,
+            min_y: self.min_y,
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The cells above, left of, right of and below `p` that are inside the grid.
+    pub fn neighbours(&self, p: Point) -> impl Iterator<Item=(Point, &T)> {
+        self.offsets(p, &ORTHOGONAL)
+    }
+
+    /// The up to eight cells surrounding `p`, including diagonals, that are inside the grid.
+    pub fn surrounding(&self, p: Point) -> impl Iterator<Item=(Point, &T)> {
+        self.offsets(p, &SURROUNDING)
+    }
+
+    fn offsets<'a>(&'a self, p: Point, offsets: &'static [Vector]) -> impl Iterator<Item=(Point, &'a T)> {
+        offsets.iter().filter_map(move |&offset| {
+            let neighbour = p + offset;
+            self.get(neighbour).map(|cell| (neighbour, cell))
+        })
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let column = x as i64 - self.min_x as i64;
+        let row = y as i64 - self.min_y as i64;
+        if column < 0 || row < 0 || column >= self.width as i64 || row >= self.height as i64 {
+            return None;
+        }
+        Some(row as usize * self.width + column as usize)
+    }
+}
+
+fn outside<T>(grid: &Grid<T>, p: Point) -> String {
+    let (min, max) = (grid.min(), grid.max());
+    format!("({}, {}) is outside the grid ({}, {}) to ({}, {})", p.x, p.y, min.x, min.y, max.x, max.y)
+}
+
+/// Indexes a cell by its position, panicking when it is outside the grid.
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        match self.get(p) {
+            Some(cell) => cell,
+            None => panic!("{}", outside(self, p)),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        if !self.contains(p) {
+            panic!("{}", outside(self, p));
+        }
+        self.get_mut(p).expect("the cell is inside the grid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered() -> Grid<i32> {
+        // 0 1 2
+        // 3 4 5
+        Grid::from_fn(3, 2, |p| p.y * 3 + p.x).with_origin(Point::new(-1, 10))
+    }
+
+    #[test]
+    fn bounds() {
+        let grid = numbered();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Point::new(-1, 10), grid.min());
+        assert_eq!(Point::new(1, 11), grid.max());
+        assert_eq!(Some(BoundingBox::new(grid.min(), grid.max())), grid.bounds());
+        assert_eq!(None, Grid::new(0, 3, 0).bounds());
+        assert!(grid.contains(Point::new(-1, 10)));
+        assert!(!grid.contains(Point::new(2, 10)));
+        assert!(!grid.contains(Point::new(0, 9)));
+        assert_eq!(Some(&4), grid.get(Point::new(0, 11)));
+        assert_eq!(None, grid.get(Point::new(0, 12)));
+        assert_eq!(5, grid[Point::new(1, 11)]);
+        assert_eq!(3, grid[Point::new(-1, 11)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "(0, 0) is outside the grid (-1, 10) to (1, 11)")]
+    fn index_outside() {
+        let _ = numbered()[Point::new(0, 0)];
+    }
+
+    #[test]
+    fn update() {
+        let mut grid = Grid::new(2, 2, '.');
+        grid[Point::new(1, 0)] = '#';
+        *grid.get_mut(Point::new(0, 1)).unwrap() = '#';
+        grid[Point::new(0, 0)] = '.';
+        assert_eq!(None, grid.get_mut(Point::new(2, 0)));
+        let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(vec![".#", "#."], rows);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = numbered();
+        assert_eq!(Some(&[3, 4, 5][..]), grid.row(11));
+        assert_eq!(None, grid.row(12));
+        assert_eq!(vec![&1, &4], grid.column(0).unwrap().collect::<Vec<_>>());
+        assert!(grid.column(2).is_none());
+        assert_eq!(2, grid.rows().count());
+    }
+
+    #[test]
+    fn iterate() {
+        let grid = numbered();
+        let cells: Vec<(Point, i32)> = grid.iter().map(|(p, v)| (p, *v)).collect();
+        assert_eq!((Point::new(-1, 10), 0), cells[0]);
+        assert_eq!((Point::new(1, 11), 5), cells[5]);
+        assert_eq!(15, grid.values().sum::<i32>());
+
+        let doubled = grid.map(|v| v * 2);
+        assert_eq!(grid.min(), doubled.min());
+        assert_eq!(Some(&10), doubled.get(Point::new(1, 11)));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = numbered();
+        let orthogonal: Vec<i32> = grid.neighbours(Point::new(-1, 10)).map(|(_, v)| *v).collect();
+        assert_eq!(vec![1, 3], orthogonal);
+        let surrounding: Vec<(i32, i32, i32)> = grid.surrounding(Point::new(0, 10)).map(|(p, v)| (p.x, p.y, *v)).collect();
+        assert_eq!(vec![(-1, 10, 0), (1, 10, 2), (-1, 11, 3), (0, 11, 4), (1, 11, 5)], surrounding);
+    }
+}
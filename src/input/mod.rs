@@ -0,0 +1,47 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+
+use {AdventError, Result};
+
+/// Name used in errors until the input is known to come from a file.
+pub const UNNAMED: &str = "<input>";
+
+/// Name of the input file that reads from stdin instead.
+pub const STDIN: &str = "-";
+
+/// Name used in errors for input read from stdin.
+pub const STDIN_NAME: &str = "<stdin>";
+
+/// Opens `filename` and hands a buffered reader to `f`, naming any error after the file.
+pub fn with_file<T, F>(filename: &str, f: F) -> Result<T>
+    where F: FnOnce(BufReader<File>) -> Result<T> {
+    let file = File::open(filename).map_err(|e| AdventError::io(filename, e))?;
+    f(BufReader::new(file)).map_err(|e| e.with_filename(filename))
+}
+
+/// Reads the lines of `input`, converting read failures into `AdventError`s.
+pub fn lines<R: BufRead>(input: R) -> impl Iterator<Item=Result<String>> {
+    input.lines().map(|line| line.map_err(|e| AdventError::io(UNNAMED, e)))
+}
+
+/// Returns the name errors should use for the given input file.
+pub fn name(filename: &str) -> &str {
+    if filename == STDIN {
+        STDIN_NAME
+    } else {
+        filename
+    }
+}
+
+/// Reads a whole puzzle input into memory, `-` reads stdin.
+pub fn read(filename: &str) -> Result<String> {
+    let mut data = String::new();
+    if filename == STDIN {
+        io::stdin().read_to_string(&mut data).map_err(|e| AdventError::io(STDIN_NAME, e))?;
+    } else {
+        with_file(filename, |mut reader| {
+            reader.read_to_string(&mut data).map_err(|e| AdventError::io(UNNAMED, e))
+        })?;
+    }
+    Ok(data)
+}
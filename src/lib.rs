@@ -1,6 +1,7 @@
-#![feature(test)]
+#![cfg_attr(feature = "nightly", feature(test))]
 
 extern crate regex;
+#[cfg(feature = "nightly")]
 extern crate test;
 extern crate rayon;
 
@@ -15,4 +16,99 @@ pub mod day8;
 pub mod day9;
 pub mod day10;
 pub mod day11;
-pub mod day12;
\ No newline at end of file
+pub mod day12;
+
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod cancel;
+pub mod circular;
+pub mod cli;
+pub mod error;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod lint;
+pub mod memory;
+pub mod parse;
+pub mod profile;
+pub mod provider;
+pub mod render;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod scheduler;
+pub mod simulation;
+pub mod summed_area;
+pub mod watch;
+
+pub use error::{AdventError, Result};
+pub use lint::Grammar;
+
+/// Where a solution reads its puzzle input from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    /// The puzzle input is read from a file, the path is relative to the crate root.
+    File(&'static str),
+}
+
+/// A single day of Advent of Code.
+///
+/// Both parts are given the contents of the puzzle input and return the answer formatted the
+/// way the puzzle expects it, or an error describing why the input could not be solved.
+///
+/// The grammar describes a valid input, so `advent lint` can report every malformed line without
+/// solving anything.
+pub trait Solution: Sync {
+    fn day(&self) -> u32;
+    fn title(&self) -> &'static str;
+    fn input(&self) -> Input;
+    fn grammar(&self) -> Grammar;
+    fn part1(&self, input: &str) -> Result<String>;
+    fn part2(&self, input: &str) -> Result<String>;
+
+    /// A picture of the puzzle for the given input, for days whose puzzle has one.
+    fn render(&self, _input: &str) -> Result<render::Image> {
+        Err(AdventError::no_answer("there is nothing to render for this day"))
+    }
+}
+
+/// Every registered solution, in day order.
+pub static SOLUTIONS: [&dyn Solution; 12] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+];
+
+/// Returns the solution registered for the given day.
+pub fn solution(day: u32) -> Option<&'static dyn Solution> {
+    SOLUTIONS.iter().find(|s| s.day() == day).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solutions_in_day_order() {
+        assert_eq!(1, SOLUTIONS[0].day());
+        for pair in SOLUTIONS.windows(2) {
+            assert!(pair[0].day() < pair[1].day());
+        }
+    }
+
+    #[test]
+    fn solution_lookup() {
+        assert_eq!("Marble Mania", solution(9).unwrap().title());
+        assert!(solution(25).is_none());
+    }
+}
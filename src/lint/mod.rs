@@ -0,0 +1,199 @@
+use regex::Regex;
+
+use input::UNNAMED;
+use {AdventError, Result};
+
+/// A regex a line of input must match, with a description of the line for error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub pattern: &'static str,
+    pub expected: &'static str,
+}
+
+impl Rule {
+    pub fn new(pattern: &'static str, expected: &'static str) -> Rule {
+        Rule { pattern, expected }
+    }
+}
+
+/// Checks the lines that matched the body rule for problems a regex cannot describe, each line
+/// is given with its 1-based line number.
+pub type Check = fn(lines: &[(usize, &str)]) -> Vec<AdventError>;
+
+/// The grammar of a day's puzzle input: optional header lines, each with its own rule, followed
+/// by body lines that all follow the same rule.
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    pub header: Vec<Rule>,
+    pub body: Rule,
+    pub min_lines: usize,
+    pub max_lines: Option<usize>,
+    pub check: Option<Check>,
+}
+
+impl Grammar {
+    /// An input of one or more lines that all follow `body`.
+    pub fn lines(body: Rule) -> Grammar {
+        Grammar {
+            header: Vec::new(),
+            body,
+            min_lines: 1,
+            max_lines: None,
+            check: None,
+        }
+    }
+
+    /// An input of exactly one line.
+    pub fn line(body: Rule) -> Grammar {
+        Grammar {
+            max_lines: Some(1),
+            ..Grammar::lines(body)
+        }
+    }
+
+    /// Adds header lines that must come before the body, in order.
+    pub fn headed_by(self, header: Vec<Rule>) -> Grammar {
+        Grammar { header, ..self }
+    }
+
+    pub fn with_check(self, check: Check) -> Grammar {
+        Grammar { check: Some(check), ..self }
+    }
+
+    /// Returns every problem with `input`, in line order. Errors are named `<input>`, use
+    /// `AdventError::with_filename` once the input file is known.
+    pub fn lint(&self, input: &str) -> Vec<AdventError> {
+        let lines: Vec<&str> = input.lines().collect();
+        let mut problems = Vec::new();
+
+        for (i, rule) in self.header.iter().enumerate() {
+            match lines.get(i) {
+                Some(line) => check_line(rule, &compile(rule), i + 1, line, &mut problems),
+                None => {
+                    problems.push(expected(rule, i + 1, ""));
+                    return problems;
+                }
+            }
+        }
+
+        let body_start = self.header.len();
+        let body: Vec<(usize, &str)> = lines.iter().enumerate().skip(body_start).map(|(i, l)| (i + 1, *l)).collect();
+        if body.len() < self.min_lines {
+            problems.push(expected(&self.body, lines.len() + 1, ""));
+        }
+        let max_lines = self.max_lines.unwrap_or(usize::MAX);
+        let regex = compile(&self.body);
+        let mut matched = Vec::new();
+        for (i, &(line_number, line)) in body.iter().enumerate() {
+            if i >= max_lines {
+                let reason = format!("expected at most {} line(s)", body_start + max_lines);
+                problems.push(AdventError::parse(UNNAMED, line_number, 1, line, &reason));
+                continue;
+            }
+            let count = problems.len();
+            check_line(&self.body, &regex, line_number, line, &mut problems);
+            if problems.len() == count {
+                matched.push((line_number, line));
+            }
+        }
+
+        if let Some(check) = self.check {
+            problems.extend(check(&matched));
+            problems.sort_by_key(|e| match *e {
+                AdventError::Parse { line, .. } => line,
+                _ => 0,
+            });
+        }
+        problems
+    }
+}
+
+/// Runs a day's parser on each line by itself, reporting its errors against that line. Used by
+/// checks for the problems the parsers already detect, such as numbers that overflow.
+pub fn each_line<T, F>(lines: &[(usize, &str)], parse: F) -> Vec<AdventError>
+    where F: Fn(&str) -> Result<T> {
+    let mut problems = Vec::new();
+    for &(line_number, line) in lines {
+        match parse(line) {
+            Ok(_) => {}
+            Err(AdventError::Parse { column, text, reason, .. }) => {
+                problems.push(AdventError::parse(UNNAMED, line_number, column, &text, &reason));
+            }
+            Err(e) => problems.push(AdventError::parse(UNNAMED, line_number, 1, line, &e.to_string())),
+        }
+    }
+    problems
+}
+
+fn compile(rule: &Rule) -> Regex {
+    Regex::new(rule.pattern).expect("bad regex")
+}
+
+fn check_line(rule: &Rule, regex: &Regex, line_number: usize, line: &str, problems: &mut Vec<AdventError>) {
+    if !regex.is_match(line) {
+        problems.push(expected(rule, line_number, line));
+    }
+}
+
+fn expected(rule: &Rule, line_number: usize, line: &str) -> AdventError {
+    AdventError::parse(UNNAMED, line_number, 1, line, &format!("expected {}", rule.expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input;
+    use std::fs;
+    use {Input, SOLUTIONS};
+
+    fn problems(grammar: &Grammar, input: &str) -> Vec<String> {
+        grammar.lint(input).iter().map(|e| e.to_string()).collect()
+    }
+
+    fn no_negatives(lines: &[(usize, &str)]) -> Vec<AdventError> {
+        lines.iter()
+            .filter(|&&(_, line)| line.starts_with('-'))
+            .map(|&(n, line)| AdventError::parse(UNNAMED, n, 1, line, "negative"))
+            .collect()
+    }
+
+    #[test]
+    fn lint_lines() {
+        let grammar = Grammar::lines(Rule::new(r"^-?\d+$", "an int")).with_check(no_negatives);
+        assert!(grammar.lint("1\n2\n3\n").is_empty());
+        assert_eq!(vec!["<input>:1:1: expected an int: ''"], problems(&grammar, ""));
+        assert_eq!(vec!["<input>:2:1: expected an int: 'x'", "<input>:3:1: negative: '-3'", "<input>:4:1: expected an int: ''"],
+                   problems(&grammar, "1\nx\n-3\n\n"));
+    }
+
+    #[test]
+    fn lint_header() {
+        let grammar = Grammar::line(Rule::new(r"^\d+$", "a count")).headed_by(vec![Rule::new(r"^count:$", "'count:'")]);
+        assert!(grammar.lint("count:\n12").is_empty());
+        assert_eq!(vec!["<input>:1:1: expected 'count:': ''"], problems(&grammar, ""));
+        assert_eq!(vec!["<input>:2:1: expected a count: ''"], problems(&grammar, "count:"));
+        assert_eq!(vec!["<input>:1:1: expected 'count:': 'total:'", "<input>:3:1: expected at most 2 line(s): '13'"],
+                   problems(&grammar, "total:\n12\n13"));
+    }
+
+    #[test]
+    fn lint_each_line() {
+        let grammar = Grammar::lines(Rule::new(r"^\d+$", "an int"))
+            .with_check(|lines| each_line(lines, |line| line.parse::<u8>()
+                .map_err(|_| AdventError::parse(UNNAMED, 1, 1, line, "not a byte"))));
+        assert_eq!(vec!["<input>:2:1: not a byte: '256'"], problems(&grammar, "255\n256"));
+    }
+
+    #[test]
+    fn puzzle_inputs_are_clean() {
+        for solution in SOLUTIONS.iter() {
+            let Input::File(filename) = solution.input();
+            // a day fresh from `advent new` has an empty placeholder until its input is saved
+            if fs::metadata(filename).map_or(true, |m| m.len() == 0) {
+                continue;
+            }
+            let input = input::read(filename).unwrap();
+            assert_eq!(Vec::<String>::new(), problems(&solution.grammar(), &input), "{}", filename);
+        }
+    }
+}
@@ -1,53 +1,412 @@
 extern crate advent;
 
-use advent::day1;
-use advent::day2;
-use advent::day3;
-use advent::day4;
-use advent::day5;
-use advent::day6;
-use advent::day7;
-use advent::day8;
-use advent::day9;
-use advent::day10;
-use advent::day11;
-use advent::day12;
-use std::time::Instant;
-
-fn time<T>(name: &str, filename: &str, f: &Fn(&str) -> T) where T: std::fmt::Debug {
-    let start = Instant::now();
-    let result = f(filename);
-    println!("{} - result: {:#?} time: {:#?}", name, result, start.elapsed());
+use advent::answers::{self, Expected};
+use advent::input;
+use advent::bench::{self, Measurement};
+use advent::cli::{self, BenchOptions, Command, Days, LintOptions, MatrixOptions, NewOptions, RenderOptions,
+                  RunOptions, VerifyOptions, WatchOptions};
+use advent::memory::CountingAllocator;
+use advent::profile::{self, Row, DEFAULT_PROFILE};
+use advent::provider::Provider;
+use advent::report::{Format, Report};
+use advent::runner::{self, Job, PARTS};
+use advent::scaffold;
+use advent::watch::{self, Watcher};
+use advent::{Input, Solution, SOLUTIONS};
+use std::collections::HashMap;
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn selected_solutions(days: &Days) -> Result<Vec<&'static dyn Solution>, String> {
+    match *days {
+        Days::All => Ok(SOLUTIONS.to_vec()),
+        Days::Day(d) => match advent::solution(d) {
+            None => Err(format!("day {} has not been solved yet", d)),
+            Some(s) => Ok(vec![s]),
+        },
+    }
 }
 
-fn time_no_file<T>(name: &str, f: &Fn() -> T) where T: std::fmt::Debug {
+/// Downloads the missing inputs of the given solutions when a server is configured.
+fn provide_inputs(solutions: &[&'static dyn Solution]) -> Result<(), String> {
+    let provider = Provider::from_env();
+    for solution in solutions {
+        if provider.provide(*solution).map_err(|e| e.to_string())? {
+            eprintln!("downloaded the input for day {}", solution.day());
+        }
+    }
+    Ok(())
+}
+
+fn run(options: &RunOptions) -> Result<(), String> {
+    let solutions = selected_solutions(&options.days)?;
+    if options.input.is_none() && options.profile.is_none() {
+        provide_inputs(&solutions)?;
+    }
+
+    // read every input up front so parallel runs only measure solving
+    let mut inputs = Vec::new();
+    for solution in solutions {
+        let filename = match options.profile {
+            Some(ref p) => profile::input_filename(solution, p),
+            None => runner::input_filename(solution, options.input.as_deref()).to_string(),
+        };
+        let input = input::read(&filename).map_err(|e| e.to_string());
+        inputs.push((solution, filename, input));
+    }
+    let mut jobs = Vec::new();
+    for &(solution, ref filename, ref input) in &inputs {
+        for &part in PARTS.iter().filter(|p| options.part.is_none_or(|o| o == **p)) {
+            jobs.push(Job { solution, part, input, filename: Some(filename) });
+        }
+    }
+
+    let stdout = io::stdout();
+    let mut report = Report::new(stdout.lock(), options.format, options.memory).map_err(|e| e.to_string())?;
     let start = Instant::now();
-    let result = f();
-    println!("{} - result: {:#?} time: {:#?}", name, result, start.elapsed());
+    let mut records = Vec::new();
+    if options.parallel {
+        // results are collected in day order, so they are only reported once every part is done
+        records = runner::record_parallel(&jobs, options.timeout);
+        for record in &records {
+            report.record(record).map_err(|e| e.to_string())?;
+        }
+    } else {
+        for job in &jobs {
+            let record = runner::record(job.solution, job.part, job.input, job.filename, options.memory,
+                                        options.timeout);
+            report.record(&record).map_err(|e| e.to_string())?;
+            records.push(record);
+        }
+    }
+    let wall_time = start.elapsed();
+    report.finish().map_err(|e| e.to_string())?;
+
+    // each part's elapsed time is wall time too, in parallel they overlap so the sum can exceed the total
+    let solve_time: Duration = records.iter().map(|r| r.elapsed).sum();
+    let summary = format!("{} part(s) - wall time: {:#?} sum of part wall times: {:#?}",
+                          records.len(), wall_time, solve_time);
+    if options.format == Format::Text {
+        println!("{}", summary);
+    } else {
+        eprintln!("{}", summary);
+    }
+
+    let failed = records.iter().filter(|r| r.answer.is_err()).count();
+    if failed > 0 {
+        return Err(format!("{} part(s) failed", failed));
+    }
+    Ok(())
+}
+
+fn list() {
+    for solution in SOLUTIONS.iter() {
+        let Input::File(input) = solution.input();
+        println!("{:>2}  {:<30} {}", solution.day(), solution.title(), input);
+    }
+}
+
+fn verify(options: &VerifyOptions) -> Result<(), String> {
+    if options.profile.is_none() {
+        provide_inputs(&SOLUTIONS)?;
+    }
+    if options.record {
+        return record(&options.answers, options.profile.as_deref(), options.timeout);
+    }
+
+    let expected_answers = answers::read(&options.answers).map_err(|e| e.to_string())?;
+    let mut failed = 0;
+    for solution in SOLUTIONS.iter() {
+        for &part in PARTS.iter() {
+            let name = format!("Day{} Part{}", solution.day(), part);
+            let expected = match answers::find(&expected_answers, solution.day(), part) {
+                None => {
+                    println!("{} - no expected answer", name);
+                    failed += 1;
+                    continue;
+                }
+                Some(e) => e,
+            };
+
+            let filename = expected.input.as_str();
+            let result = input::read(filename)
+                .and_then(|input| runner::time_within(*solution, part, &input, Some(filename), options.timeout).0);
+            match result {
+                Ok(ref answer) if *answer == expected.answer => println!("{} - ok", name),
+                Ok(answer) => {
+                    println!("{} - mismatch: expected {:?} got {:?}", name, expected.answer, answer);
+                    failed += 1;
+                }
+                Err(e) => {
+                    println!("{} - error: {}", name, e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} part(s) did not match {}", failed, options.answers));
+    }
+    Ok(())
+}
+
+fn record(filename: &str, profile: Option<&str>, timeout: Option<Duration>) -> Result<(), String> {
+    let mut recorded = Vec::new();
+    for solution in SOLUTIONS.iter() {
+        let input_filename = profile::input_filename(*solution, profile.unwrap_or(DEFAULT_PROFILE));
+        // a profile only needs inputs for the days its account has
+        if profile.is_some() && !Path::new(&input_filename).exists() {
+            println!("Day{} - skipped, {} is missing", solution.day(), input_filename);
+            continue;
+        }
+        let input = input::read(&input_filename).map_err(|e| e.to_string())?;
+        for &part in PARTS.iter() {
+            let answer = runner::time_within(*solution, part, &input, Some(&input_filename), timeout).0
+                .map_err(|e| e.to_string())?;
+            recorded.push(Expected {
+                day: solution.day(),
+                part,
+                input: input_filename.clone(),
+                answer,
+            });
+        }
+    }
+
+    answers::write_file(filename, &recorded).map_err(|e| e.to_string())?;
+    println!("recorded {} answers in {}", recorded.len(), filename);
+    Ok(())
+}
+
+fn matrix(options: &MatrixOptions) -> Result<(), String> {
+    let mut profiles = options.profiles.clone();
+    if profiles.is_empty() {
+        profiles.push(DEFAULT_PROFILE.to_string());
+        profiles.extend(profile::list(profile::DATA_DIR).map_err(|e| e.to_string())?);
+    }
+    let mut expected = Vec::new();
+    for p in &profiles {
+        let filename = profile::answers_filename(p);
+        let answers = if Path::new(&filename).exists() {
+            answers::read(&filename).map_err(|e| e.to_string())?
+        } else {
+            Vec::new()
+        };
+        expected.push(answers);
+    }
+
+    let parts: Vec<u32> = PARTS.iter().cloned().filter(|p| options.part.is_none_or(|o| o == *p)).collect();
+    let mut rows: Vec<Row> = Vec::new();
+    for solution in SOLUTIONS.iter() {
+        let start = rows.len();
+        for &part in &parts {
+            rows.push(Row { day: solution.day(), part, cells: Vec::new() });
+        }
+        for (p, answers) in profiles.iter().zip(&expected) {
+            let cells = profile::check(*solution, &parts, p, answers, options.timeout);
+            for (row, cell) in rows[start..].iter_mut().zip(cells) {
+                row.cells.push(cell);
+            }
+        }
+    }
+
+    let stdout = io::stdout();
+    profile::write_matrix(stdout.lock(), &profiles, &rows).map_err(|e| e.to_string())?;
+    let failed = rows.iter().flat_map(|r| &r.cells).filter(|c| c.failed()).count();
+    if failed > 0 {
+        return Err(format!("{} check(s) failed", failed));
+    }
+    Ok(())
+}
+
+fn bench(options: &BenchOptions) -> Result<(), String> {
+    let baseline = match options.baseline {
+        None => None,
+        Some(ref f) => Some(bench::read(f).map_err(|e| e.to_string())?),
+    };
+
+    let solutions = selected_solutions(&options.days)?;
+    provide_inputs(&solutions)?;
+    let mut measurements = Vec::new();
+    for solution in solutions {
+        let filename = runner::input_filename(solution, None);
+        let input = input::read(filename).map_err(|e| e.to_string())?;
+        for &part in PARTS.iter().filter(|p| options.part.is_none_or(|o| o == **p)) {
+            // make sure the part can be solved in time before timing it, the timed runs then
+            // finish too so they are not slowed down by a thread each
+            runner::time_within(solution, part, &input, Some(filename), options.timeout).0
+                .map_err(|e| e.to_string())?;
+            let stats = bench::measure(options.warmup, options.iterations,
+                                       || runner::solve(solution, part, &input, Some(filename)));
+            println!("Day{} Part{} - min: {:#?} median: {:#?} mean: {:#?} stddev: {:#?}",
+                     solution.day(), part, stats.min, stats.median, stats.mean, stats.stddev);
+            measurements.push(Measurement { day: solution.day(), part, stats });
+        }
+    }
+
+    bench::write_file(&options.output, &measurements).map_err(|e| e.to_string())?;
+    println!("results written to {}", options.output);
+
+    if let Some(baseline) = baseline {
+        let regressions = bench::compare(&baseline, &measurements, options.threshold);
+        for r in &regressions {
+            println!("Day{} Part{} - regression: median {:#?} -> {:#?} (+{:.1}%)",
+                     r.day, r.part, r.baseline, r.current, r.increase);
+        }
+        if !regressions.is_empty() {
+            return Err(format!("{} part(s) slowed down by more than {}%", regressions.len(), options.threshold));
+        }
+    }
+    Ok(())
+}
+
+fn watch(options: &WatchOptions) -> Result<(), String> {
+    let solution = advent::solution(options.day)
+        .ok_or_else(|| format!("day {} has not been solved yet", options.day))?;
+    if options.input.is_none() {
+        provide_inputs(&[solution])?;
+    }
+    let filenames = vec![
+        runner::input_filename(solution, options.input.as_deref()).to_string(),
+        watch::example_filename(solution.day()),
+    ];
+
+    let mut watcher = Watcher::new(&filenames);
+    let mut previous: HashMap<(String, u32), String> = HashMap::new();
+    println!("watching {}", filenames.join(", "));
+    loop {
+        // the example file is optional, the puzzle input is not
+        for (i, filename) in filenames.iter().enumerate() {
+            if i > 0 && !Path::new(filename).exists() {
+                continue;
+            }
+            let input = match input::read(filename) {
+                Ok(input) => input,
+                Err(e) => {
+                    println!("Day{} - error: {}", solution.day(), e);
+                    continue;
+                }
+            };
+            for &part in PARTS.iter().filter(|p| options.part.is_none_or(|o| o == **p)) {
+                let name = format!("Day{} Part{} [{}]", solution.day(), part, filename);
+                let answer = match runner::time_within(solution, part, &input, Some(filename), options.timeout) {
+                    (Ok(answer), elapsed) => {
+                        println!("{} - result: {} time: {:#?}", name, answer, elapsed);
+                        answer
+                    }
+                    (Err(e), _) => {
+                        println!("{} - error: {}", name, e);
+                        continue;
+                    }
+                };
+                match previous.insert((filename.clone(), part), answer.clone()) {
+                    None => {}
+                    Some(ref last) if *last == answer => println!("    unchanged"),
+                    Some(last) => {
+                        for line in watch::diff(&last, &answer) {
+                            println!("    {}", line);
+                        }
+                    }
+                }
+            }
+        }
+
+        loop {
+            thread::sleep(Duration::from_millis(options.interval));
+            let changed = watcher.changed();
+            if !changed.is_empty() {
+                println!("changed: {}", changed.join(", "));
+                break;
+            }
+        }
+    }
+}
+
+fn lint(options: &LintOptions) -> Result<(), String> {
+    let solution = advent::solution(options.day)
+        .ok_or_else(|| format!("day {} has not been solved yet", options.day))?;
+    let filename = runner::input_filename(solution, options.input.as_deref());
+    let input = input::read(filename).map_err(|e| e.to_string())?;
+
+    let problems = solution.grammar().lint(&input);
+    let count = problems.len();
+    for problem in problems {
+        println!("{}", problem.with_filename(input::name(filename)));
+    }
+    if count > 0 {
+        return Err(format!("{} problem(s) in {}", count, input::name(filename)));
+    }
+    println!("{} - ok", input::name(filename));
+    Ok(())
+}
+
+fn render(options: &RenderOptions) -> Result<(), String> {
+    let solution = advent::solution(options.day)
+        .ok_or_else(|| format!("day {} has not been solved yet", options.day))?;
+    let filename = runner::input_filename(solution, options.input.as_deref());
+    let input = input::read(filename).map_err(|e| e.to_string())?;
+    let image = runner::render(solution, &input, Some(filename), options.timeout).map_err(|e| e.to_string())?;
+
+    if options.ascii {
+        print!("{}", image.to_ascii());
+        return Ok(());
+    }
+    let output = match options.output {
+        Some(ref output) => output.clone(),
+        None => format!("target/day{}.{}", options.day, image.extension()),
+    };
+    image.save(&output, options.encoding).map_err(|e| e.to_string())?;
+    println!("wrote {} ({}x{})", output, image.width(), image.height());
+    Ok(())
+}
+
+fn new(options: &NewOptions) -> Result<(), String> {
+    let created = scaffold::create(Path::new("."), options.day, &options.title).map_err(|e| e.to_string())?;
+    for filename in &created {
+        println!("wrote {}", filename.strip_prefix(".").unwrap_or(filename).display());
+    }
+    println!("record the answers with 'advent verify --record' once day {} is solved", options.day);
+    Ok(())
 }
 
 fn main() {
-    time("Day1 Part1", "data/day1-input.txt", &day1::part1);
-    time("Day1 Part2", "data/day1-input.txt", &day1::part2);
-    time("Day2 Part1", "data/day2-input.txt", &day2::part1);
-    time("Day2 Part2", "data/day2-input.txt", &day2::part2);
-    time("Day3 Part1", "data/day3-input.txt", &day3::part1);
-    time("Day3 Part2", "data/day3-input.txt", &day3::part2);
-    time("Day4 Part1", "data/day4-input.txt", &day4::part1);
-    time("Day4 Part2", "data/day4-input.txt", &day4::part2);
-    time("Day5 Part1", "data/day5-input.txt", &day5::part1);
-    time("Day5 Part2", "data/day5-input.txt", &day5::part2);
-    time("Day6 Part1", "data/day6-input.txt", &day6::part1);
-    time("Day6 Part2", "data/day6-input.txt", &day6::part2);
-    time("Day7 Part1", "data/day7-input.txt", &day7::part1);
-    time("Day7 Part2", "data/day7-input.txt", &day7::part2);
-    time("Day8 Part1", "data/day8-input.txt", &day8::part1);
-    time("Day8 Part2", "data/day8-input.txt", &day8::part2);
-    time_no_file("Day9 Part1", &day9::part1);
-    time_no_file("Day9 Part2", &day9::part2);
-    time("Day10 Part1 & 2", "data/day10-input.txt", &day10::part1_and_2);
-    time_no_file("Day11 Part1", &day11::part1);
-    time_no_file("Day11 Part1", &day11::part2);
-    time_no_file("Day12 Part1", &day12::part1);
-    time_no_file("Day12 Part1", &day12::part2);
-}
\ No newline at end of file
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match cli::parse(&args) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    let result = match command {
+        Command::Run(options) => run(&options),
+        Command::List => {
+            list();
+            Ok(())
+        }
+        Command::Verify(options) => verify(&options),
+        Command::Matrix(options) => matrix(&options),
+        Command::Bench(options) => bench(&options),
+        Command::Watch(options) => watch(&options),
+        Command::Lint(options) => lint(&options),
+        Command::Render(options) => render(&options),
+        Command::New(options) => new(&options),
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
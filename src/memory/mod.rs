@@ -0,0 +1,133 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// What one thread has allocated, `live` goes below zero when the thread frees memory another
+/// thread allocated.
+#[derive(Clone, Copy)]
+struct Counts {
+    allocations: usize,
+    bytes: usize,
+    live: isize,
+    peak: isize,
+}
+
+thread_local! {
+    static COUNTS: Cell<Counts> = const { Cell::new(Counts { allocations: 0, bytes: 0, live: 0, peak: 0 }) };
+}
+
+/// A global allocator that forwards to the system allocator while counting allocations.
+///
+/// `measure` only reports non-zero statistics in a binary that installs it with
+/// `#[global_allocator]`. Each thread has its own counters, so a part left running after a
+/// timeout cannot skew the statistics of later parts, but neither is work a solution hands to
+/// other threads, such as rayon's pool, counted.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            freed(layout.size());
+            allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn update<F: FnOnce(&mut Counts)>(f: F) {
+    // the counters are gone while a thread shuts down, its last frees go uncounted
+    let _ = COUNTS.try_with(|cell| {
+        let mut counts = cell.get();
+        f(&mut counts);
+        cell.set(counts);
+    });
+}
+
+fn allocated(size: usize) {
+    update(|c| {
+        c.allocations = c.allocations.wrapping_add(1);
+        c.bytes = c.bytes.wrapping_add(size);
+        c.live = c.live.wrapping_add(size as isize);
+        c.peak = c.peak.max(c.live);
+    });
+}
+
+fn freed(size: usize) {
+    update(|c| c.live = c.live.wrapping_sub(size as isize));
+}
+
+/// Allocations made while running a closure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub allocations: usize,
+    /// Total bytes requested, a reallocation counts its full new size.
+    pub bytes: usize,
+    /// The most bytes that were live at once, above what was live before the closure ran.
+    pub peak: usize,
+}
+
+/// Runs `f`, counting the allocations it makes on the current thread.
+pub fn measure<T, F>(f: F) -> (T, Stats)
+    where F: FnOnce() -> T {
+    update(|c| c.peak = c.live);
+    let before = COUNTS.with(Cell::get);
+
+    let result = f();
+
+    let after = COUNTS.with(Cell::get);
+    let stats = Stats {
+        allocations: after.allocations.wrapping_sub(before.allocations),
+        bytes: after.bytes.wrapping_sub(before.bytes),
+        peak: after.peak.saturating_sub(before.live).max(0) as usize,
+    };
+    (result, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn measure_counts_allocations() {
+        let (total, stats) = measure(|| {
+            let values: Vec<u64> = (0..1000).collect();
+            values.iter().sum::<u64>()
+        });
+        assert_eq!(499500, total);
+        assert_eq!(Stats { allocations: 1, bytes: 8000, peak: 8000 }, stats);
+    }
+
+    #[test]
+    fn other_threads_are_not_counted() {
+        // spawning allocates a little on this thread, the thread's buffer is allocated on its own
+        let (length, stats) = measure(|| thread::spawn(|| vec![0u8; 1 << 20].len()).join().unwrap());
+        assert_eq!(1 << 20, length);
+        assert!(stats.bytes < 1 << 20);
+    }
+}
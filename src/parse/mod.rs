@@ -0,0 +1,197 @@
+use std::io::BufRead;
+
+use regex::{Captures, Regex};
+
+use input::{self, UNNAMED};
+use {AdventError, Result};
+
+/// A value that can be read from a single capture group.
+pub trait Field: Sized {
+    /// The reason given when a capture is not a valid value.
+    const REASON: &'static str;
+
+    fn parse_field(text: &str) -> Option<Self>;
+}
+
+macro_rules! int_field {
+    ($($t:ty),*) => {$(
+        impl Field for $t {
+            const REASON: &'static str = "not an int";
+
+            fn parse_field(text: &str) -> Option<$t> {
+                text.parse().ok()
+            }
+        }
+    )*};
+}
+
+int_field!(i32, i64, u32, u64, usize);
+
+impl Field for char {
+    const REASON: &'static str = "not a single character";
+
+    fn parse_field(text: &str) -> Option<char> {
+        text.parse().ok()
+    }
+}
+
+impl Field for String {
+    const REASON: &'static str = "not a string";
+
+    fn parse_field(text: &str) -> Option<String> {
+        Some(text.to_string())
+    }
+}
+
+/// The capture groups of one matching line, errors point at the group that failed.
+pub struct Fields<'t> {
+    line_number: usize,
+    caps: Captures<'t>,
+}
+
+impl<'t> Fields<'t> {
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// The text of capture group `n`, empty when the group did not take part in the match.
+    pub fn text(&self, n: usize) -> &'t str {
+        self.caps.get(n).map_or("", |m| m.as_str())
+    }
+
+    /// An error about the whole match, for checks that involve several groups.
+    pub fn error(&self, reason: &str) -> AdventError {
+        AdventError::parse(UNNAMED, self.line_number, 1, self.text(0), reason)
+    }
+
+    /// Parses capture group `n` as a `T`.
+    pub fn get<T: Field>(&self, n: usize) -> Result<T> {
+        self.map(n, T::REASON, T::parse_field)
+    }
+
+    /// Converts capture group `n` with `f`, failing with `reason` when `f` returns `None`.
+    pub fn map<T, F>(&self, n: usize, reason: &str, f: F) -> Result<T>
+        where F: FnOnce(&str) -> Option<T> {
+        let (column, text) = match self.caps.get(n) {
+            Some(m) => (m.start() + 1, m.as_str()),
+            None => (1, self.caps.get(0).map_or("", |m| m.as_str())),
+        };
+        f(text).ok_or_else(|| AdventError::parse(UNNAMED, self.line_number, column, text, reason))
+    }
+}
+
+/// A value built from the capture groups of a line, implemented for structs by hand and for
+/// tuples of `Field`s, whose elements are taken from groups 1, 2, ... in order.
+pub trait FromFields: Sized {
+    fn from_fields(fields: &Fields) -> Result<Self>;
+}
+
+macro_rules! tuple_from_fields {
+    ($($t:ident $n:expr),*) => {
+        impl<$($t: Field),*> FromFields for ($($t,)*) {
+            fn from_fields(fields: &Fields) -> Result<($($t,)*)> {
+                Ok(($(fields.get::<$t>($n)?,)*))
+            }
+        }
+    };
+}
+
+tuple_from_fields!(A 1);
+tuple_from_fields!(A 1, B 2);
+tuple_from_fields!(A 1, B 2, C 3);
+tuple_from_fields!(A 1, B 2, C 3, D 4);
+tuple_from_fields!(A 1, B 2, C 3, D 4, E 5);
+tuple_from_fields!(A 1, B 2, C 3, D 4, E 5, F 6);
+
+/// Parses lines that match a regex into values, such as `Vec<(i32, i32)>` from `r"^(\d+), (\d+)$"`.
+pub struct LineParser {
+    regex: Regex,
+}
+
+impl LineParser {
+    /// Compiles the line pattern, panicking when it is not a valid regex.
+    pub fn new(pattern: &str) -> LineParser {
+        LineParser {
+            regex: Regex::new(pattern).expect("bad regex"),
+        }
+    }
+
+    /// Parses every line of `reader`, stopping at the first line that does not match or parse.
+    pub fn parse<T: FromFields, R: BufRead>(&self, reader: R) -> Result<Vec<T>> {
+        let mut values = Vec::new();
+        for (i, line) in input::lines(reader).enumerate() {
+            values.push(self.parse_line(i + 1, &line?)?);
+        }
+        Ok(values)
+    }
+
+    /// Parses a single line, `line_number` is used in errors.
+    pub fn parse_line<T: FromFields>(&self, line_number: usize, line: &str) -> Result<T> {
+        self.match_line(line_number, line)
+            .unwrap_or_else(|| Err(AdventError::parse(UNNAMED, line_number, 1, line, "line does not match regex")))
+    }
+
+    /// Parses a line if it matches, for inputs that mix several kinds of line.
+    pub fn match_line<T: FromFields>(&self, line_number: usize, line: &str) -> Option<Result<T>> {
+        self.regex.captures(line).map(|caps| T::from_fields(&Fields { line_number, caps }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Move {
+        name: String,
+        dx: i32,
+        dy: i32,
+    }
+
+    impl FromFields for Move {
+        fn from_fields(fields: &Fields) -> Result<Move> {
+            Ok(Move {
+                name: fields.map(1, "not a name", |s| Some(s.to_uppercase()))?,
+                dx: fields.get(2)?,
+                dy: fields.get(3)?,
+            })
+        }
+    }
+
+    #[test]
+    fn parse_tuples() {
+        let parser = LineParser::new(r"^(\d+), (-?\d+)$");
+        let points: Vec<(usize, i32)> = parser.parse("1, 2\n3, -4".as_bytes()).unwrap();
+        assert_eq!(vec![(1, 2), (3, -4)], points);
+
+        let letters: Vec<(char, char)> = LineParser::new(r"(\w) before (\w)").parse("A before B".as_bytes()).unwrap();
+        assert_eq!(vec![('A', 'B')], letters);
+    }
+
+    #[test]
+    fn parse_structs() {
+        let parser = LineParser::new(r"^(\w+) moves (-?\d+),(-?\d+)$");
+        let moves: Vec<Move> = parser.parse("elf moves 1,-1".as_bytes()).unwrap();
+        assert_eq!(vec![Move { name: "ELF".to_string(), dx: 1, dy: -1 }], moves);
+    }
+
+    #[test]
+    fn errors_name_the_line_and_column() {
+        let parser = LineParser::new(r"^(\d+), (\w+)$");
+        let e = parser.parse::<(i32, u32), _>("1, 2\n3, x".as_bytes()).unwrap_err();
+        assert_eq!("<input>:2:4: not an int: 'x'", e.to_string());
+
+        let e = parser.parse::<(i32, u32), _>("1, 2\n\n".as_bytes()).unwrap_err();
+        assert_eq!("<input>:2:1: line does not match regex: ''", e.to_string());
+
+        let e = parser.parse::<(char, u32), _>("12, 2".as_bytes()).unwrap_err();
+        assert_eq!("<input>:1:1: not a single character: '12'", e.to_string());
+    }
+
+    #[test]
+    fn match_line() {
+        let parser = LineParser::new(r"Guard #(\d+) begins");
+        assert_eq!(Some(10), parser.match_line::<(usize,)>(3, "Guard #10 begins shift").map(|r| r.unwrap().0));
+        assert!(parser.match_line::<(usize,)>(3, "falls asleep").is_none());
+    }
+}
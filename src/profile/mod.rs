@@ -0,0 +1,237 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::result;
+use std::time::Duration;
+
+use answers::{self, Expected};
+use input;
+use runner;
+use {AdventError, Input, Result, Solution};
+
+/// The directory holding the default inputs, every subdirectory of it is a profile.
+pub const DATA_DIR: &str = "data";
+
+/// The name the matrix uses for the inputs kept directly in `data/`.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Checks that a profile name can be used as a directory inside `data/`.
+pub fn validate(profile: &str) -> result::Result<(), String> {
+    let valid = !profile.is_empty() && profile != "." && profile != ".."
+        && profile.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a valid profile name", profile))
+    }
+}
+
+/// Lists the profiles in `data_dir`, sorted by name. The default profile is not included.
+pub fn list(data_dir: &str) -> Result<Vec<String>> {
+    let entries = fs::read_dir(data_dir).map_err(|e| AdventError::io(data_dir, e))?;
+    let mut profiles = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| AdventError::io(data_dir, e))?;
+        if entry.path().is_dir() {
+            if let Some(name) = entry.file_name().to_str() {
+                if name != DEFAULT_PROFILE && validate(name).is_ok() {
+                    profiles.push(name.to_string());
+                }
+            }
+        }
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
+/// Returns the input a solution reads for a profile: `data/day7-input.txt` becomes
+/// `data/<profile>/day7-input.txt`. The default profile reads the solution's own input.
+pub fn input_filename(solution: &dyn Solution, profile: &str) -> String {
+    let Input::File(f) = solution.input();
+    if profile == DEFAULT_PROFILE {
+        return f.to_string();
+    }
+    let path = Path::new(f);
+    let name = path.file_name().map(Path::new).unwrap_or(path);
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    dir.join(profile).join(name).to_string_lossy().into_owned()
+}
+
+/// Returns the expected answers manifest of a profile.
+pub fn answers_filename(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        answers::DEFAULT_FILE.to_string()
+    } else {
+        format!("{}/{}/answers.txt", DATA_DIR, profile)
+    }
+}
+
+/// How one part fared against one profile's input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The answer matched the profile's expected answer.
+    Pass,
+    /// The answer did not match, holds the answer that was given.
+    Fail(String),
+    /// The part was solved but the profile has no expected answer for it.
+    Unchecked,
+    /// The part could not be solved.
+    Error(String),
+    /// The profile has no input for the day.
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+impl Cell {
+    fn missing() -> Cell {
+        Cell { outcome: Outcome::Missing, elapsed: Duration::from_secs(0) }
+    }
+
+    /// Whether the cell is a failed check or an error, missing inputs and answers are not failures.
+    pub fn failed(&self) -> bool {
+        matches!(self.outcome, Outcome::Fail(_) | Outcome::Error(_))
+    }
+}
+
+/// Runs both parts of a solution against a profile's input and compares them with the
+/// profile's expected answers, giving up on a part once `timeout` has passed.
+pub fn check(solution: &'static dyn Solution, parts: &[u32], profile: &str, expected: &[Expected],
+             timeout: Option<Duration>) -> Vec<Cell> {
+    let filename = input_filename(solution, profile);
+    if !Path::new(&filename).exists() {
+        return parts.iter().map(|_| Cell::missing()).collect();
+    }
+    let input = input::read(&filename);
+
+    parts.iter().map(|&part| {
+        let (answer, elapsed) = match input {
+            Ok(ref i) => runner::time_within(solution, part, i, Some(&filename), timeout),
+            Err(ref e) => return Cell { outcome: Outcome::Error(e.to_string()), elapsed: Duration::from_secs(0) },
+        };
+        let outcome = match (answer, answers::find(expected, solution.day(), part)) {
+            (Err(e), _) => Outcome::Error(e.to_string()),
+            (Ok(_), None) => Outcome::Unchecked,
+            (Ok(ref a), Some(e)) if *a == e.answer => Outcome::Pass,
+            (Ok(a), Some(_)) => Outcome::Fail(a),
+        };
+        Cell { outcome, elapsed }
+    }).collect()
+}
+
+/// One day and part of the matrix, with a cell per profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub day: u32,
+    pub part: u32,
+    pub cells: Vec<Cell>,
+}
+
+const CELL_WIDTH: usize = 20;
+
+/// Writes the matrix as a table with a column per profile, followed by each failure in full.
+pub fn write_matrix<W: Write>(mut writer: W, profiles: &[String], rows: &[Row]) -> io::Result<()> {
+    let mut line = "Day Part".to_string();
+    for profile in profiles {
+        line += &format!("  {:<width$}", profile, width = CELL_WIDTH);
+    }
+    writeln!(writer, "{}", line.trim_end())?;
+
+    for row in rows {
+        let mut line = format!("{:>3} {:>4}", row.day, row.part);
+        for cell in &row.cells {
+            let text = match cell.outcome {
+                Outcome::Pass => format!("pass {:.1?}", cell.elapsed),
+                Outcome::Fail(_) => format!("FAIL {:.1?}", cell.elapsed),
+                Outcome::Unchecked => format!("ok? {:.1?}", cell.elapsed),
+                Outcome::Error(_) => "ERROR".to_string(),
+                Outcome::Missing => "-".to_string(),
+            };
+            line += &format!("  {:<width$}", text, width = CELL_WIDTH);
+        }
+        writeln!(writer, "{}", line.trim_end())?;
+    }
+
+    for row in rows {
+        for (profile, cell) in profiles.iter().zip(&row.cells) {
+            match cell.outcome {
+                Outcome::Fail(ref a) => writeln!(writer, "Day{} Part{} {} - wrong answer: {:?}", row.day, row.part, profile, a)?,
+                Outcome::Error(ref e) => writeln!(writer, "Day{} Part{} {} - error: {}", row.day, row.part, profile, e)?,
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn profile_names() {
+        assert!(validate("alice").is_ok());
+        assert!(validate("bob-2").is_ok());
+        assert!(validate("..").is_err());
+        assert!(validate("a/b").is_err());
+        assert!(validate("").is_err());
+    }
+
+    #[test]
+    fn profile_files() {
+        assert_eq!("data/day7-input.txt", input_filename(&::day7::Day7, DEFAULT_PROFILE));
+        assert_eq!("data/alice/day7-input.txt", input_filename(&::day7::Day7, "alice"));
+        assert_eq!("data/answers.txt", answers_filename(DEFAULT_PROFILE));
+        assert_eq!("data/alice/answers.txt", answers_filename("alice"));
+    }
+
+    #[test]
+    fn list_profiles() {
+        let dir = env::temp_dir().join(format!("advent-profiles-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("bob")).unwrap();
+        fs::create_dir_all(dir.join("alice")).unwrap();
+        fs::write(dir.join("answers.txt"), "").unwrap();
+
+        let profiles = list(dir.to_str().unwrap()).unwrap();
+        assert_eq!(vec!["alice", "bob"], profiles);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_profile_input() {
+        let cells = check(&::day1::Day1, &[1, 2], "no-such-profile", &[], None);
+        assert_eq!(vec![Cell::missing(), Cell::missing()], cells);
+        assert!(!cells[0].failed());
+    }
+
+    #[test]
+    fn matrix() {
+        let ms = Duration::from_millis(3);
+        let profiles = vec!["default".to_string(), "alice".to_string()];
+        let rows = vec![
+            Row { day: 1, part: 1, cells: vec![
+                Cell { outcome: Outcome::Pass, elapsed: ms },
+                Cell { outcome: Outcome::Fail("17".to_string()), elapsed: ms },
+            ] },
+            Row { day: 1, part: 2, cells: vec![
+                Cell { outcome: Outcome::Unchecked, elapsed: ms },
+                Cell::missing(),
+            ] },
+        ];
+        let mut out = Vec::new();
+        write_matrix(&mut out, &profiles, &rows).unwrap();
+        let expected = "Day Part  default               alice
+  1    1  pass 3.0ms            FAIL 3.0ms
+  1    2  ok? 3.0ms             -
+Day1 Part1 alice - wrong answer: \"17\"
+";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+}
@@ -0,0 +1,218 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::time::Duration;
+
+use {AdventError, Input, Result, Solution};
+
+/// Environment variable holding the server puzzle inputs are downloaded from, for example
+/// `http://localhost:8080/2018`. Only plain HTTP is supported.
+pub const BASE_URL_VAR: &str = "ADVENT_BASE_URL";
+
+/// Environment variable holding the session token sent with every download.
+pub const SESSION_VAR: &str = "ADVENT_SESSION";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Downloads puzzle inputs that are missing from `data/`.
+///
+/// Inputs are fetched from `<base url>/day/<day>/input` and cached in the file the solution
+/// reads, an input that is already on disk is never downloaded again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Provider {
+    base_url: Option<String>,
+    session: Option<String>,
+}
+
+impl Provider {
+    pub fn new(base_url: Option<String>, session: Option<String>) -> Provider {
+        Provider { base_url, session }
+    }
+
+    /// Configures the provider from `ADVENT_BASE_URL` and `ADVENT_SESSION`.
+    pub fn from_env() -> Provider {
+        Provider::new(env::var(BASE_URL_VAR).ok(), env::var(SESSION_VAR).ok())
+    }
+
+    /// Makes sure a solution's own input file exists, downloading it when it is missing and a
+    /// server is configured. Returns whether the input was downloaded.
+    pub fn provide(&self, solution: &dyn Solution) -> Result<bool> {
+        match solution.input() {
+            Input::File(filename) if self.base_url.is_some() => self.fetch(solution.day(), filename),
+            _ => Ok(false),
+        }
+    }
+
+    /// Downloads the input for `day` into `filename` unless the file already exists.
+    pub fn fetch(&self, day: u32, filename: &str) -> Result<bool> {
+        if Path::new(filename).exists() {
+            return Ok(false);
+        }
+        let base_url = self.base_url.as_ref()
+            .ok_or_else(|| AdventError::io(filename, other_error(&format!("{} is not set", BASE_URL_VAR))))?;
+        let session = self.session.as_ref()
+            .ok_or_else(|| AdventError::io(filename, other_error(&format!("{} is not set", SESSION_VAR))))?;
+
+        let url = format!("{}/day/{}/input", base_url.trim_end_matches('/'), day);
+        let body = get(&url, session).map_err(|e| AdventError::io(&url, e))?;
+
+        // write to a temporary file first so an interrupted download is never mistaken for an input
+        let partial = format!("{}.part", filename);
+        if let Some(dir) = Path::new(filename).parent() {
+            fs::create_dir_all(dir).map_err(|e| AdventError::io(filename, e))?;
+        }
+        fs::write(&partial, &body).map_err(|e| AdventError::io(&partial, e))?;
+        fs::rename(&partial, filename).map_err(|e| AdventError::io(filename, e))?;
+        Ok(true)
+    }
+}
+
+fn other_error(reason: &str) -> io::Error {
+    io::Error::other(reason.to_string())
+}
+
+/// Splits `http://host[:port]/path` into the address to connect to, the host header and the path.
+fn parse_url(url: &str) -> io::Result<(String, String, String)> {
+    if !url.starts_with("http://") {
+        return Err(other_error("only http:// urls are supported"));
+    }
+    let rest = &url["http://".len()..];
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    if host.is_empty() {
+        return Err(other_error("the url has no host"));
+    }
+    let address = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+    Ok((address, host.to_string(), path.to_string()))
+}
+
+/// Sends an HTTP/1.0 GET, so the response is never chunked, and returns the body of a 200 response.
+fn get(url: &str, session: &str) -> io::Result<Vec<u8>> {
+    let (address, host, path) = parse_url(url)?;
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\nCookie: session={}\r\nUser-Agent: advent\r\n\r\n",
+           path, host, session)?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status = String::new();
+    reader.read_line(&mut status)?;
+    let code = status.split_whitespace().nth(1);
+    if code != Some("200") {
+        return Err(other_error(&format!("server responded '{}'", status.trim_end())));
+    }
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+        if let Some(i) = header.find(':') {
+            if header[..i].eq_ignore_ascii_case("content-length") {
+                content_length = header[i + 1..].trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = Vec::new();
+    reader.read_to_end(&mut body)?;
+    if content_length.is_some_and(|l| l != body.len()) {
+        return Err(other_error("the response was cut short"));
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::process;
+    use std::thread;
+
+    /// Serves a single request, returning the request line and cookie it was sent.
+    fn serve_once(response: &'static str) -> (String, thread::JoinHandle<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2018", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut cookie = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                if line.starts_with("Cookie: ") {
+                    cookie = line.trim_end().to_string();
+                } else if request.is_empty() {
+                    request = line.trim_end().to_string();
+                }
+            }
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            (request, cookie)
+        });
+        (base_url, server)
+    }
+
+    fn cache_file(name: &str) -> String {
+        let filename = env::temp_dir().join(format!("advent-provider-{}/{}", process::id(), name));
+        let filename = filename.to_str().unwrap().to_string();
+        let _ = fs::remove_file(&filename);
+        filename
+    }
+
+    #[test]
+    fn fetch_and_cache() {
+        let (base_url, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n+1\n-2\n");
+        let provider = Provider::new(Some(base_url), Some("secret".to_string()));
+        let filename = cache_file("day1-input.txt");
+
+        assert!(provider.fetch(1, &filename).unwrap());
+        let (request, cookie) = server.join().unwrap();
+        assert_eq!("GET /2018/day/1/input HTTP/1.0", request);
+        assert_eq!("Cookie: session=secret", cookie);
+        assert_eq!("+1\n-2\n", fs::read_to_string(&filename).unwrap());
+
+        // the server only answers once, so a second download would fail
+        assert!(!provider.fetch(1, &filename).unwrap());
+        fs::remove_file(&filename).unwrap();
+    }
+
+    #[test]
+    fn failed_download_is_not_cached() {
+        let (base_url, server) = serve_once("HTTP/1.1 404 Not Found\r\n\r\nPlease don't repeatedly request this endpoint");
+        let provider = Provider::new(Some(base_url.clone()), Some("secret".to_string()));
+        let filename = cache_file("day2-input.txt");
+
+        let e = provider.fetch(2, &filename).unwrap_err();
+        server.join().unwrap();
+        assert_eq!(format!("{}/day/2/input: server responded 'HTTP/1.1 404 Not Found'", base_url), e.to_string());
+        assert!(!Path::new(&filename).exists());
+    }
+
+    #[test]
+    fn missing_configuration() {
+        let filename = cache_file("day3-input.txt");
+        let provider = Provider::new(Some("http://127.0.0.1:1".to_string()), None);
+        assert_eq!(format!("{}: {} is not set", filename, SESSION_VAR),
+                   provider.fetch(3, &filename).unwrap_err().to_string());
+        let provider = Provider::new(None, None);
+        assert!(!provider.provide(&::day3::Day3).unwrap());
+    }
+
+    #[test]
+    fn urls() {
+        assert_eq!(("localhost:8080".to_string(), "localhost:8080".to_string(), "/2018/day/1/input".to_string()),
+                   parse_url("http://localhost:8080/2018/day/1/input").unwrap());
+        assert_eq!(("example.com:80".to_string(), "example.com".to_string(), "/".to_string()),
+                   parse_url("http://example.com").unwrap());
+        assert!(parse_url("https://example.com/").is_err());
+    }
+}
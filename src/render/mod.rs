@@ -0,0 +1,318 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use geometry::{BoundingBox, Point};
+use grid::Grid;
+use {AdventError, Result};
+
+/// Netpbm readers are asked to handle plain lines up to 70 characters.
+const PLAIN_LINE_WIDTH: usize = 70;
+
+/// Characters from light to dark used to draw grey levels as text.
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// The perceived brightness of the colour.
+    pub fn grey(self) -> u8 {
+        let Rgb(r, g, b) = self;
+        ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+    }
+}
+
+/// How the samples of a Netpbm image are written: `Plain` is ASCII decimal numbers, `Binary`
+/// is raw bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Plain,
+    Binary,
+}
+
+/// A picture of a puzzle, one cell per pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Image {
+    /// Black and white, `true` is black as in PBM.
+    Bitmap(Grid<bool>),
+    /// Grey levels, 0 is black and 255 white.
+    Greymap(Grid<u8>),
+    Pixmap(Grid<Rgb>),
+}
+
+impl Image {
+    pub fn width(&self) -> usize {
+        match *self {
+            Image::Bitmap(ref grid) => grid.width(),
+            Image::Greymap(ref grid) => grid.width(),
+            Image::Pixmap(ref grid) => grid.width(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match *self {
+            Image::Bitmap(ref grid) => grid.height(),
+            Image::Greymap(ref grid) => grid.height(),
+            Image::Pixmap(ref grid) => grid.height(),
+        }
+    }
+
+    /// The file extension of the Netpbm format the image is written in.
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            Image::Bitmap(_) => "pbm",
+            Image::Greymap(_) => "pgm",
+            Image::Pixmap(_) => "ppm",
+        }
+    }
+
+    /// Writes the image as a PBM, PGM or PPM file.
+    pub fn write<W: Write>(&self, writer: &mut W, encoding: Encoding) -> io::Result<()> {
+        let magic = match (self, encoding) {
+            (&Image::Bitmap(_), Encoding::Plain) => "P1",
+            (&Image::Greymap(_), Encoding::Plain) => "P2",
+            (&Image::Pixmap(_), Encoding::Plain) => "P3",
+            (&Image::Bitmap(_), Encoding::Binary) => "P4",
+            (&Image::Greymap(_), Encoding::Binary) => "P5",
+            (&Image::Pixmap(_), Encoding::Binary) => "P6",
+        };
+        writeln!(writer, "{}\n{} {}", magic, self.width(), self.height())?;
+
+        match *self {
+            Image::Bitmap(ref grid) => match encoding {
+                Encoding::Plain => write_plain(writer, grid, |&black| vec![black as u8]),
+                Encoding::Binary => {
+                    // eight pixels to a byte, first pixel in the high bit, every row padded
+                    for row in grid.rows() {
+                        let bytes: Vec<u8> = row.chunks(8).map(|pixels| {
+                            pixels.iter().enumerate().fold(0, |byte, (i, &black)| byte | (black as u8) << (7 - i))
+                        }).collect();
+                        writer.write_all(&bytes)?;
+                    }
+                    Ok(())
+                }
+            },
+            Image::Greymap(ref grid) => {
+                writeln!(writer, "255")?;
+                match encoding {
+                    Encoding::Plain => write_plain(writer, grid, |&grey| vec![grey]),
+                    Encoding::Binary => write_binary(writer, grid, |&grey| vec![grey]),
+                }
+            }
+            Image::Pixmap(ref grid) => {
+                writeln!(writer, "255")?;
+                match encoding {
+                    Encoding::Plain => write_plain(writer, grid, |&Rgb(r, g, b)| vec![r, g, b]),
+                    Encoding::Binary => write_binary(writer, grid, |&Rgb(r, g, b)| vec![r, g, b]),
+                }
+            }
+        }
+    }
+
+    /// Writes the image to `filename`, replacing any file that is there.
+    pub fn save(&self, filename: &str, encoding: Encoding) -> Result<()> {
+        File::create(filename)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                self.write(&mut writer, encoding)?;
+                writer.flush()
+            })
+            .map_err(|e| AdventError::io(filename, e))
+    }
+
+    /// Draws the image as text, `#` for black and `.` for white in a bitmap and a ramp of
+    /// characters from light to dark otherwise.
+    pub fn to_ascii(&self) -> String {
+        let ramp = |grey: u8| ASCII_RAMP[(255 - grey) as usize * (ASCII_RAMP.len() - 1) / 255] as char;
+        match *self {
+            Image::Bitmap(ref grid) => ascii(grid, |&black| if black { '#' } else { '.' }),
+            Image::Greymap(ref grid) => ascii(grid, |&grey| ramp(grey)),
+            Image::Pixmap(ref grid) => ascii(grid, |&colour| ramp(colour.grey())),
+        }
+    }
+}
+
+fn write_plain<W, T, F>(writer: &mut W, grid: &Grid<T>, samples: F) -> io::Result<()>
+    where W: Write, F: Fn(&T) -> Vec<u8> {
+    for row in grid.rows() {
+        let mut line = String::new();
+        for sample in row.iter().flat_map(&samples) {
+            let sample = sample.to_string();
+            if !line.is_empty() && line.len() + 1 + sample.len() > PLAIN_LINE_WIDTH {
+                writeln!(writer, "{}", line)?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&sample);
+        }
+        writeln!(writer, "{}", line)?;
+    }
+    Ok(())
+}
+
+fn write_binary<W, T, F>(writer: &mut W, grid: &Grid<T>, samples: F) -> io::Result<()>
+    where W: Write, F: Fn(&T) -> Vec<u8> {
+    let bytes: Vec<u8> = grid.values().flat_map(samples).collect();
+    writer.write_all(&bytes)
+}
+
+/// Draws a grid as text, one line per row.
+pub fn ascii<T, F>(grid: &Grid<T>, mut f: F) -> String
+    where F: FnMut(&T) -> char {
+    let mut out = String::with_capacity((grid.width() + 1) * grid.height());
+    for row in grid.rows() {
+        out.extend(row.iter().map(&mut f));
+        out.push('\n');
+    }
+    out
+}
+
+/// A grid just large enough to hold every point, the points are `true`.
+pub fn points<I>(points: I) -> Grid<bool>
+    where I: IntoIterator<Item=Point> {
+    let points: Vec<Point> = points.into_iter().collect();
+    let bounds = match BoundingBox::from_points(points.iter().cloned()) {
+        None => return Grid::new(0, 0, false),
+        Some(bounds) => bounds,
+    };
+    let mut grid = Grid::new(bounds.width(), bounds.height(), false).with_origin(bounds.min);
+    for p in points {
+        grid[p] = true;
+    }
+    grid
+}
+
+/// Colours spread evenly from 0.0 to 1.0, in between colours are blended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    stops: Vec<Rgb>,
+}
+
+impl Palette {
+    /// Panics without any colours.
+    pub fn new(stops: Vec<Rgb>) -> Palette {
+        assert!(!stops.is_empty(), "a palette needs at least one colour");
+        Palette { stops }
+    }
+
+    /// Black to white.
+    pub fn greys() -> Palette {
+        Palette::new(vec![Rgb::BLACK, Rgb::WHITE])
+    }
+
+    /// Black through red and yellow to white, for heatmaps.
+    pub fn heat() -> Palette {
+        Palette::new(vec![Rgb::BLACK, Rgb(255, 0, 0), Rgb(255, 255, 0), Rgb::WHITE])
+    }
+
+    /// The colour at `t`, which is clamped to 0.0 to 1.0.
+    pub fn colour(&self, t: f64) -> Rgb {
+        let last = self.stops.len() - 1;
+        let position = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) * last as f64 };
+        let i = (position.floor() as usize).min(last);
+        let (from, to) = (self.stops[i], self.stops[(i + 1).min(last)]);
+        let blend = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * (position - i as f64)).round() as u8;
+        Rgb(blend(from.0, to.0), blend(from.1, to.1), blend(from.2, to.2))
+    }
+}
+
+/// Colours a grid of numbers, the smallest value gets the palette's first colour and the
+/// largest its last.
+pub fn heatmap<T, F>(grid: &Grid<T>, palette: &Palette, value: F) -> Grid<Rgb>
+    where F: Fn(&T) -> f64 {
+    let (min, max) = grid.values().map(&value).fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    let range = max - min;
+    grid.map(|cell| {
+        let t = if range > 0.0 { (value(cell) - min) / range } else { 0.0 };
+        palette.colour(t)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(image: &Image, encoding: Encoding) -> Vec<u8> {
+        let mut out = Vec::new();
+        image.write(&mut out, encoding).unwrap();
+        out
+    }
+
+    fn bitmap() -> Image {
+        // #.........
+        // .#........
+        Image::Bitmap(Grid::from_fn(10, 2, |p| p.x == p.y))
+    }
+
+    #[test]
+    fn bitmaps() {
+        let image = bitmap();
+        assert_eq!("pbm", image.extension());
+        assert_eq!("P1\n10 2\n1 0 0 0 0 0 0 0 0 0\n0 1 0 0 0 0 0 0 0 0\n",
+                   String::from_utf8(written(&image, Encoding::Plain)).unwrap());
+        assert_eq!(b"P4\n10 2\n\x80\x00\x40\x00".to_vec(), written(&image, Encoding::Binary));
+        assert_eq!("#.........\n.#........\n", image.to_ascii());
+    }
+
+    #[test]
+    fn greymaps_and_pixmaps() {
+        let grey = Image::Greymap(Grid::from_fn(2, 1, |p| p.x as u8 * 255));
+        assert_eq!("P2\n2 1\n255\n0 255\n", String::from_utf8(written(&grey, Encoding::Plain)).unwrap());
+        assert_eq!(b"P5\n2 1\n255\n\x00\xff".to_vec(), written(&grey, Encoding::Binary));
+        assert_eq!("@ \n", grey.to_ascii());
+
+        let colour = Image::Pixmap(Grid::new(1, 1, Rgb(1, 2, 3)));
+        assert_eq!("P3\n1 1\n255\n1 2 3\n", String::from_utf8(written(&colour, Encoding::Plain)).unwrap());
+        assert_eq!(b"P6\n1 1\n255\n\x01\x02\x03".to_vec(), written(&colour, Encoding::Binary));
+    }
+
+    #[test]
+    fn plain_lines_are_wrapped() {
+        let image = Image::Greymap(Grid::new(30, 1, 200));
+        let out = String::from_utf8(written(&image, Encoding::Plain)).unwrap();
+        assert!(out.lines().all(|line| line.len() <= PLAIN_LINE_WIDTH));
+        assert_eq!(30, out.lines().skip(3).flat_map(|line| line.split(' ')).count());
+    }
+
+    #[test]
+    fn save_to_file() {
+        let filename = ::std::env::temp_dir().join(format!("advent-render-{}.pbm", ::std::process::id()));
+        let filename = filename.to_string_lossy().to_string();
+        bitmap().save(&filename, Encoding::Binary).unwrap();
+        assert_eq!(written(&bitmap(), Encoding::Binary), ::std::fs::read(&filename).unwrap());
+        ::std::fs::remove_file(&filename).unwrap();
+
+        let e = bitmap().save("/no/such/dir/image.pbm", Encoding::Plain).unwrap_err();
+        assert!(e.to_string().starts_with("/no/such/dir/image.pbm: "));
+    }
+
+    #[test]
+    fn point_sets() {
+        let grid = points(vec![Point::new(-1, 5), Point::new(1, 6)]);
+        assert_eq!(Point::new(-1, 5), grid.min());
+        assert_eq!("#..\n..#\n", ascii(&grid, |&p| if p { '#' } else { '.' }));
+        assert_eq!(0, points(Vec::new()).width());
+    }
+
+    #[test]
+    fn palettes() {
+        let heat = Palette::heat();
+        assert_eq!(Rgb::BLACK, heat.colour(0.0));
+        assert_eq!(Rgb(255, 0, 0), heat.colour(1.0 / 3.0));
+        assert_eq!(Rgb(255, 128, 0), heat.colour(0.5));
+        assert_eq!(Rgb::WHITE, heat.colour(2.0));
+        assert_eq!(Rgb(7, 7, 7), Palette::new(vec![Rgb(7, 7, 7)]).colour(0.5));
+
+        let grid = Grid::from_fn(3, 1, |p| p.x * 10 - 5);
+        let map = heatmap(&grid, &Palette::greys(), |&v| v as f64);
+        assert_eq!(vec![Rgb::BLACK, Rgb(128, 128, 128), Rgb::WHITE], map.values().cloned().collect::<Vec<_>>());
+        assert_eq!(Rgb::BLACK, heatmap(&Grid::new(2, 2, 3), &Palette::greys(), |&v| v as f64)[Point::new(1, 1)]);
+    }
+}
@@ -0,0 +1,304 @@
+use std::io::{self, Write};
+use std::time::Duration;
+
+use memory;
+
+/// How run results are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            "markdown" | "md" => Some(Format::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// The outcome of running one part of a day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub day: u32,
+    pub part: u32,
+    pub input: Option<String>,
+    /// The answer, or the message of the error that prevented solving the part.
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+    /// Allocations made while solving, when they were measured.
+    pub memory: Option<memory::Stats>,
+    /// Whether the part was abandoned for running past its time budget.
+    pub timed_out: bool,
+}
+
+/// Streams records to a writer in the chosen format, call `finish` once every record is written.
+///
+/// When the report includes memory, the allocation columns are added to the CSV and Markdown
+/// tables and every record with memory statistics reports them.
+pub struct Report<W: Write> {
+    writer: W,
+    format: Format,
+    memory: bool,
+    count: usize,
+}
+
+impl<W: Write> Report<W> {
+    pub fn new(mut writer: W, format: Format, memory: bool) -> io::Result<Report<W>> {
+        match format {
+            Format::Text => {}
+            Format::Json => write!(writer, "[")?,
+            Format::Csv => {
+                write!(writer, "day,part,input,status,answer,error,elapsed_ns")?;
+                if memory {
+                    write!(writer, ",allocations,allocated_bytes,peak_bytes")?;
+                }
+                writeln!(writer)?;
+            }
+            Format::Markdown => {
+                if memory {
+                    writeln!(writer, "| Day | Part | Input | Status | Answer | Time | Allocations | Allocated | Peak |")?;
+                    writeln!(writer, "|----:|-----:|-------|--------|--------|-----:|------------:|----------:|-----:|")?;
+                } else {
+                    writeln!(writer, "| Day | Part | Input | Status | Answer | Time |")?;
+                    writeln!(writer, "|----:|-----:|-------|--------|--------|-----:|")?;
+                }
+            }
+        }
+        Ok(Report { writer, format, memory, count: 0 })
+    }
+
+    pub fn record(&mut self, r: &Record) -> io::Result<()> {
+        let input = r.input.as_deref().unwrap_or("");
+        let (status, answer, error) = match r.answer {
+            Ok(ref a) => ("ok", a.as_str(), ""),
+            Err(ref e) if r.timed_out => ("timeout", "", e.as_str()),
+            Err(ref e) => ("error", "", e.as_str()),
+        };
+        let memory = if self.memory { r.memory } else { None };
+
+        match self.format {
+            Format::Text => {
+                match r.answer {
+                    Ok(ref a) => {
+                        // multi-line answers (rendered images) start on their own line
+                        let separator = if a.contains('\n') { "\n" } else { "" };
+                        write!(self.writer, "Day{} Part{} - result: {}{}", r.day, r.part, separator, a)?;
+                    }
+                    Err(ref e) if r.timed_out => write!(self.writer, "Day{} Part{} - {}", r.day, r.part, e)?,
+                    Err(ref e) => write!(self.writer, "Day{} Part{} - error: {}", r.day, r.part, e)?,
+                }
+                write!(self.writer, " time: {:#?}", r.elapsed)?;
+                if let Some(m) = memory {
+                    write!(self.writer, " allocations: {} allocated: {} bytes peak: {} bytes",
+                           m.allocations, m.bytes, m.peak)?;
+                }
+                writeln!(self.writer)?;
+            }
+            Format::Json => {
+                let separator = if self.count == 0 { "" } else { "," };
+                write!(self.writer, "{}\n  {{\"day\": {}, \"part\": {}, \"input\": {}, \"status\": \"{}\", ",
+                       separator, r.day, r.part, json_string(input), status)?;
+                match r.answer {
+                    Ok(ref a) => write!(self.writer, "\"answer\": {}, ", json_string(a))?,
+                    Err(ref e) => write!(self.writer, "\"error\": {}, ", json_string(e))?,
+                }
+                write!(self.writer, "\"elapsed_ns\": {}", r.elapsed.as_nanos())?;
+                if let Some(m) = memory {
+                    write!(self.writer, ", \"allocations\": {}, \"allocated_bytes\": {}, \"peak_bytes\": {}",
+                           m.allocations, m.bytes, m.peak)?;
+                }
+                write!(self.writer, "}}")?;
+            }
+            Format::Csv => {
+                write!(self.writer, "{},{},{},{},{},{},{}", r.day, r.part, csv_field(input), status,
+                       csv_field(answer), csv_field(error), r.elapsed.as_nanos())?;
+                if self.memory {
+                    match memory {
+                        Some(m) => write!(self.writer, ",{},{},{}", m.allocations, m.bytes, m.peak)?,
+                        None => write!(self.writer, ",,,")?,
+                    }
+                }
+                writeln!(self.writer)?;
+            }
+            Format::Markdown => {
+                let text = if r.answer.is_ok() { answer } else { error };
+                write!(self.writer, "| {} | {} | {} | {} | {} | {:#?} |", r.day, r.part, markdown_cell(input),
+                       status, markdown_cell(text), r.elapsed)?;
+                if self.memory {
+                    match memory {
+                        Some(m) => write!(self.writer, " {} | {} | {} |", m.allocations, m.bytes, m.peak)?,
+                        None => write!(self.writer, "  |  |  |")?,
+                    }
+                }
+                writeln!(self.writer)?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        if self.format == Format::Json {
+            let separator = if self.count == 0 { "" } else { "\n" };
+            writeln!(self.writer, "{}]", separator)?;
+        }
+        self.writer.flush()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn markdown_cell(s: &str) -> String {
+    s.trim_end_matches('\n').replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 1,
+                part: 1,
+                input: Some("data/day1-input.txt".to_string()),
+                answer: Ok("592".to_string()),
+                elapsed: Duration::from_micros(120),
+                memory: Some(memory::Stats { allocations: 3, bytes: 4096, peak: 2048 }),
+                timed_out: false,
+            },
+            Record {
+                day: 3,
+                part: 2,
+                input: None,
+                answer: Err("bad.txt:2:1: line does not match regex: '#2 @ a,2'".to_string()),
+                elapsed: Duration::from_nanos(15),
+                memory: None,
+                timed_out: false,
+            },
+        ]
+    }
+
+    fn write_report(format: Format, memory: bool) -> String {
+        let mut out = Vec::new();
+        let mut report = Report::new(&mut out, format, memory).unwrap();
+        for r in &records() {
+            report.record(r).unwrap();
+        }
+        report.finish().unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn json_report() {
+        let expected = r#"[
+  {"day": 1, "part": 1, "input": "data/day1-input.txt", "status": "ok", "answer": "592", "elapsed_ns": 120000},
+  {"day": 3, "part": 2, "input": "", "status": "error", "error": "bad.txt:2:1: line does not match regex: '#2 @ a,2'", "elapsed_ns": 15}
+]
+"#;
+        assert_eq!(expected, write_report(Format::Json, false));
+    }
+
+    #[test]
+    fn empty_json_report() {
+        let mut out = Vec::new();
+        Report::new(&mut out, Format::Json, false).unwrap().finish().unwrap();
+        assert_eq!("[]\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn csv_report() {
+        let expected = r#"day,part,input,status,answer,error,elapsed_ns
+1,1,data/day1-input.txt,ok,592,,120000
+3,2,,error,,"bad.txt:2:1: line does not match regex: '#2 @ a,2'",15
+"#;
+        assert_eq!(expected, write_report(Format::Csv, false));
+    }
+
+    #[test]
+    fn markdown_report() {
+        let expected = "| Day | Part | Input | Status | Answer | Time |
+|----:|-----:|-------|--------|--------|-----:|
+| 1 | 1 | data/day1-input.txt | ok | 592 | 120µs |
+| 3 | 2 |  | error | bad.txt:2:1: line does not match regex: '#2 @ a,2' | 15ns |
+";
+        assert_eq!(expected, write_report(Format::Markdown, false));
+    }
+
+    #[test]
+    fn memory_reports() {
+        let expected = "Day1 Part1 - result: 592 time: 120µs allocations: 3 allocated: 4096 bytes peak: 2048 bytes
+Day3 Part2 - error: bad.txt:2:1: line does not match regex: '#2 @ a,2' time: 15ns
+";
+        assert_eq!(expected, write_report(Format::Text, true));
+
+        let expected = r#"day,part,input,status,answer,error,elapsed_ns,allocations,allocated_bytes,peak_bytes
+1,1,data/day1-input.txt,ok,592,,120000,3,4096,2048
+3,2,,error,,"bad.txt:2:1: line does not match regex: '#2 @ a,2'",15,,,
+"#;
+        assert_eq!(expected, write_report(Format::Csv, true));
+        assert!(write_report(Format::Json, true).contains(r#""elapsed_ns": 120000, "allocations": 3, "#));
+    }
+
+    #[test]
+    fn timeout_report() {
+        let record = Record {
+            day: 1,
+            part: 2,
+            input: None,
+            answer: Err("TIMEOUT after 1s".to_string()),
+            elapsed: Duration::from_secs(1),
+            memory: None,
+            timed_out: true,
+        };
+        let mut out = Vec::new();
+        let mut report = Report::new(&mut out, Format::Csv, false).unwrap();
+        report.record(&record).unwrap();
+        report.finish().unwrap();
+        assert!(String::from_utf8(out).unwrap().ends_with("\n1,2,,timeout,,TIMEOUT after 1s,1000000000\n"));
+
+        let mut out = Vec::new();
+        let mut report = Report::new(&mut out, Format::Text, false).unwrap();
+        report.record(&record).unwrap();
+        assert_eq!("Day1 Part2 - TIMEOUT after 1s time: 1s\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(r#""a\"b\\c\nd\u0001""#, json_string("a\"b\\c\nd\u{1}"));
+        assert_eq!("\"#.\n.#\"", csv_field("#.\n.#"));
+        assert_eq!("#.<br>a\\|b", markdown_cell("#.\na|b\n"));
+    }
+}
@@ -0,0 +1,263 @@
+use std::panic;
+use std::result;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use cancel::{self, Token};
+use input;
+use memory::{self, Stats};
+use render::Image;
+use report::Record;
+use {AdventError, Input, Result, Solution};
+
+/// Both parts of a puzzle, in order.
+pub const PARTS: [u32; 2] = [1, 2];
+
+/// Returns the input file a solution should read, `filename` overrides the solution's own input.
+pub fn input_filename<'a>(solution: &dyn Solution, filename: Option<&'a str>) -> &'a str {
+    let Input::File(own) = solution.input();
+    filename.unwrap_or(own)
+}
+
+/// Solves one part of a puzzle, naming any error after the input file it was found in.
+pub fn solve(solution: &dyn Solution, part: u32, input: &str, filename: Option<&str>) -> Result<String> {
+    let result = match part {
+        1 => solution.part1(input),
+        _ => solution.part2(input),
+    };
+    match filename {
+        None => result,
+        Some(f) => result.map_err(|e| e.with_filename(input::name(f))),
+    }
+}
+
+/// Solves one part of a puzzle, returning how long the solution took along with the answer.
+pub fn time(solution: &dyn Solution, part: u32, input: &str, filename: Option<&str>) -> (Result<String>, Duration) {
+    let start = Instant::now();
+    let result = solve(solution, part, input, filename);
+    (result, start.elapsed())
+}
+
+/// Solves one part of a puzzle on its own thread, giving up once `timeout` has passed.
+///
+/// The solution is then asked to stop through its cancellation token, a solution that never
+/// checks the token is left running in the background until the process exits.
+pub fn time_with_timeout(solution: &'static dyn Solution, part: u32, input: &str, filename: Option<&str>,
+                         timeout: Duration) -> (Result<String>, Duration) {
+    measure_with_timeout(solution, part, input, filename, false, timeout).0
+}
+
+/// Solves one part of a puzzle, giving up once `timeout` has passed when there is one.
+pub fn time_within(solution: &'static dyn Solution, part: u32, input: &str, filename: Option<&str>,
+                   timeout: Option<Duration>) -> (Result<String>, Duration) {
+    match timeout {
+        Some(t) => time_with_timeout(solution, part, input, filename, t),
+        None => time(solution, part, input, filename),
+    }
+}
+
+/// Times one part of a puzzle, counting its allocations when `measure_memory` is set.
+fn measure(solution: &dyn Solution, part: u32, input: &str, filename: Option<&str>,
+           measure_memory: bool) -> ((Result<String>, Duration), Option<Stats>) {
+    if measure_memory {
+        let (timed, stats) = memory::measure(|| time(solution, part, input, filename));
+        (timed, Some(stats))
+    } else {
+        (time(solution, part, input, filename), None)
+    }
+}
+
+/// Like `measure` on the thread of `with_timeout`. The allocations are counted on that thread so
+/// the copy of the input and the thread itself are left out.
+fn measure_with_timeout(solution: &'static dyn Solution, part: u32, input: &str, filename: Option<&str>,
+                        measure_memory: bool, timeout: Duration) -> ((Result<String>, Duration), Option<Stats>) {
+    let input = input.to_string();
+    let filename = filename.map(|f| f.to_string());
+    let measured = with_timeout(timeout, move || {
+        measure(solution, part, &input, filename.as_deref(), measure_memory)
+    });
+    match measured {
+        Ok(measured) => measured,
+        Err(e @ AdventError::Timeout(_)) => ((Err(e), timeout), None),
+        Err(e) => ((Err(e), Duration::from_secs(0)), None),
+    }
+}
+
+/// Draws a solution's picture of `input`, giving up once `timeout` has passed when there is one.
+pub fn render(solution: &'static dyn Solution, input: &str, filename: Option<&str>,
+              timeout: Option<Duration>) -> Result<Image> {
+    let result = match timeout {
+        Some(t) => {
+            let input = input.to_string();
+            with_timeout(t, move || solution.render(&input)).and_then(|image| image)
+        }
+        None => solution.render(input),
+    };
+    match filename {
+        None => result,
+        Some(f) => result.map_err(|e| e.with_filename(input::name(f))),
+    }
+}
+
+/// Runs `f` on its own thread with a fresh cancellation token, cancelling it once `timeout` has
+/// passed.
+fn with_timeout<T, F>(timeout: Duration, f: F) -> Result<T>
+    where T: Send + 'static, F: FnOnce() -> T + Send + 'static {
+    let token = Token::new();
+    let (sender, receiver) = mpsc::channel();
+    let solver_token = token.clone();
+    thread::spawn(move || {
+        let _ = sender.send(cancel::with_token(solver_token, f));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(value) => Ok(value),
+        Err(RecvTimeoutError::Timeout) => {
+            token.cancel();
+            Err(AdventError::Timeout(timeout))
+        }
+        Err(RecvTimeoutError::Disconnected) => Err(AdventError::no_answer("the solution panicked")),
+    }
+}
+
+/// Runs one part of a puzzle and records the outcome, `input` is the error message when the
+/// input could not be read. With `measure_memory` the allocations made while solving are counted,
+/// with a `timeout` the part is abandoned once it has run for that long.
+pub fn record(solution: &'static dyn Solution, part: u32, input: &result::Result<String, String>,
+              filename: Option<&str>, measure_memory: bool, timeout: Option<Duration>) -> Record {
+    let ((answer, elapsed), memory) = match (input.as_ref(), timeout) {
+        (Ok(i), Some(t)) => measure_with_timeout(solution, part, i, filename, measure_memory, t),
+        (Ok(i), None) => measure(solution, part, i, filename, measure_memory),
+        (Err(e), _) => return Record {
+            day: solution.day(),
+            part,
+            input: filename.map(|f| f.to_string()),
+            answer: Err(e.clone()),
+            elapsed: Duration::from_secs(0),
+            memory: None,
+            timed_out: false,
+        },
+    };
+    let timed_out = matches!(answer, Err(AdventError::Timeout(_)));
+    Record {
+        day: solution.day(),
+        part,
+        input: filename.map(|f| f.to_string()),
+        answer: answer.map_err(|e| e.to_string()),
+        elapsed,
+        memory,
+        timed_out,
+    }
+}
+
+/// One part of a puzzle for `record_parallel`, `input` is as for `record`.
+pub struct Job<'a> {
+    pub solution: &'static dyn Solution,
+    pub part: u32,
+    pub input: &'a result::Result<String, String>,
+    pub filename: Option<&'a str>,
+}
+
+/// Records every job at once, returning the records in the order of the jobs.
+///
+/// Each job waits for its answer on a thread of its own rather than on a rayon worker, so
+/// solutions that use rayon always have the whole pool even while other jobs wait out a timeout.
+pub fn record_parallel(jobs: &[Job], timeout: Option<Duration>) -> Vec<Record> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = jobs.iter()
+            .map(|job| scope.spawn(move || record(job.solution, job.part, job.input, job.filename, false, timeout)))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e))).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day1::Day1;
+    use day10::Day10;
+    use lint::{Grammar, Rule};
+    use rayon::prelude::*;
+    use rayon::ThreadPoolBuilder;
+
+    /// Answers both parts with a sum spread over the global rayon pool.
+    struct ParallelSum;
+
+    impl Solution for ParallelSum {
+        fn day(&self) -> u32 {
+            1
+        }
+
+        fn title(&self) -> &'static str {
+            "Parallel Sum"
+        }
+
+        fn input(&self) -> Input {
+            Input::File("")
+        }
+
+        fn grammar(&self) -> Grammar {
+            Grammar::lines(Rule::new(r"^$", "nothing"))
+        }
+
+        fn part1(&self, _input: &str) -> Result<String> {
+            Ok((1..101u32).into_par_iter().sum::<u32>().to_string())
+        }
+
+        fn part2(&self, input: &str) -> Result<String> {
+            self.part1(input)
+        }
+    }
+
+    #[test]
+    fn timeout() {
+        // the frequency never repeats, so part 2 only stops when it is cancelled
+        let (answer, elapsed) = time_with_timeout(&Day1, 2, "+1", None, Duration::from_millis(50));
+        assert_eq!("TIMEOUT after 50ms", answer.unwrap_err().to_string());
+        assert_eq!(Duration::from_millis(50), elapsed);
+
+        let (answer, _) = time_with_timeout(&Day1, 2, "+1\n-1", None, Duration::from_secs(10));
+        assert_eq!("0", answer.unwrap());
+    }
+
+    #[test]
+    fn render_timeout() {
+        // lights that never move never spread out again
+        let input = "position=< 1,  1> velocity=< 0,  0>\nposition=< 3,  1> velocity=< 0,  0>";
+        let e = render(&Day10, input, None, Some(Duration::from_millis(50))).unwrap_err();
+        assert_eq!("TIMEOUT after 50ms", e.to_string());
+
+        // two lights meet after one second
+        let input = "position=< 0,  0> velocity=< 1,  0>\nposition=< 2,  0> velocity=<-1,  0>";
+        let image = render(&Day10, input, None, Some(Duration::from_secs(10))).unwrap();
+        assert_eq!("#\n", image.to_ascii());
+    }
+
+    #[test]
+    fn record_timeout() {
+        let input = Ok("+1".to_string());
+        let timed_out = record(&Day1, 2, &input, None, true, Some(Duration::from_millis(50)));
+        assert!(timed_out.timed_out);
+        assert_eq!(None, timed_out.memory);
+
+        let input = Ok("+1\n-1".to_string());
+        let solved = record(&Day1, 2, &input, None, true, Some(Duration::from_secs(10)));
+        assert_eq!(Ok("0".to_string()), solved.answer);
+        assert!(solved.memory.is_some());
+    }
+
+    #[test]
+    fn parallel_jobs_leave_the_pool_free() {
+        // with a single worker, a job waiting for its answer on that worker left the solution
+        // nothing to run on until it timed out; the pool may already exist with more workers
+        let _ = ThreadPoolBuilder::new().num_threads(1).build_global();
+        let input = Ok(String::new());
+        let jobs: Vec<Job> = (0..4)
+            .map(|i| Job { solution: &ParallelSum, part: PARTS[i % 2], input: &input, filename: None })
+            .collect();
+        let records = record_parallel(&jobs, Some(Duration::from_secs(5)));
+        let answers: Vec<_> = records.into_iter().map(|r| r.answer).collect();
+        assert_eq!(vec![Ok("5050".to_string()); 4], answers);
+    }
+}
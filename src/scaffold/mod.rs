@@ -0,0 +1,303 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use watch;
+use {AdventError, Result};
+
+/// The file every day module is registered in, relative to the crate root.
+pub const LIB_FILE: &str = "src/lib.rs";
+
+const MODULE_TEMPLATE: &str = r#"use std::io::BufRead;
+
+use input::{self, UNNAMED};
+use lint::{self, Rule};
+use {AdventError, Grammar, Input, Result, Solution};
+
+/*
+File Format:
+1
+2
+3
+*/
+fn parse_values<R: BufRead>(reader: R) -> Result<Vec<i32>> {
+    let mut values = Vec::new();
+    for (i, line) in input::lines(reader).enumerate() {
+        let line = line?;
+        let value = line.trim().parse::<i32>()
+            .map_err(|_| AdventError::parse(UNNAMED, i + 1, 1, &line, "not an int"))?;
+        values.push(value);
+    }
+    Ok(values)
+}
+
+pub fn part1(filename: &str) -> Result<i32> {
+    input::with_file(filename, part1_from)
+}
+
+pub fn part1_from<R: BufRead>(reader: R) -> Result<i32> {
+    let values = parse_values(reader)?;
+    Ok(values.iter().sum())
+}
+
+pub fn part2(filename: &str) -> Result<i32> {
+    input::with_file(filename, part2_from)
+}
+
+pub fn part2_from<R: BufRead>(reader: R) -> Result<i32> {
+    parse_values(reader)?;
+    Err(AdventError::no_answer("part 2 has not been solved yet"))
+}
+
+pub struct Day__DAY__;
+
+impl Solution for Day__DAY__ {
+    fn day(&self) -> u32 {
+        __DAY__
+    }
+
+    fn title(&self) -> &'static str {
+        "__TITLE__"
+    }
+
+    fn input(&self) -> Input {
+        Input::File("data/day__DAY__-input.txt")
+    }
+
+    fn grammar(&self) -> Grammar {
+        Grammar::lines(Rule::new(r"^-?\d+$", "an int"))
+            .with_check(|lines| lint::each_line(lines, |line| parse_values(line.as_bytes())))
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        part1_from(input.as_bytes()).map(|a| a.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        part2_from(input.as_bytes()).map(|a| a.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "nightly")]
+    use test::Bencher;
+
+    use super::*;
+
+    const EXAMPLE: &str = "1
+2
+3";
+
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn part1_bench(b: &mut Bencher) {
+        b.iter(|| part1("data/day__DAY__-input.txt"));
+    }
+
+    #[test]
+    fn part1_from_example() {
+        assert_eq!(6, part1_from(EXAMPLE.as_bytes()).unwrap());
+    }
+}
+"#;
+
+/// Returns the source of a new day module, a skeleton with a parser, both parts, an example
+/// test and a benchmark.
+pub fn module_source(day: u32, title: &str) -> String {
+    MODULE_TEMPLATE
+        .replace("__DAY__", &day.to_string())
+        .replace("__TITLE__", &title.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Adds a day to the source of `lib.rs`: declares its module and adds it to `SOLUTIONS`,
+/// keeping both in day order.
+pub fn register(source: &str, day: u32) -> Result<String> {
+    let module = format!("pub mod day{};", day);
+    let entry = format!("    &day{0}::Day{0},", day);
+    let lines: Vec<&str> = source.lines().collect();
+
+    let mut last_module = None;
+    let mut module_at = None;
+    let mut table = None;
+    let mut entry_at = None;
+    let mut table_end = None;
+    for (i, line) in lines.iter().enumerate() {
+        if *line == module || *line == entry {
+            return Err(AdventError::parse(LIB_FILE, i + 1, 1, line, &format!("day {} is already registered", day)));
+        }
+        if let Some(d) = registered_day(line, "pub mod day", ";") {
+            last_module = Some(i);
+            if d > day && module_at.is_none() {
+                module_at = Some(i);
+            }
+        }
+        if line.starts_with("pub static SOLUTIONS: [&dyn Solution; ") {
+            table = Some(i);
+        }
+        if table.is_some() && table_end.is_none() {
+            if let Some(d) = registered_day(line.trim(), "&day", "") {
+                if d > day && entry_at.is_none() {
+                    entry_at = Some(i);
+                }
+            } else if *line == "];" {
+                table_end = Some(i);
+            }
+        }
+    }
+
+    let last_module = last_module.ok_or_else(|| AdventError::parse(LIB_FILE, 1, 1, "", "no day modules found"))?;
+    let (table, table_end) = match (table, table_end) {
+        (Some(t), Some(e)) => (t, e),
+        _ => return Err(AdventError::parse(LIB_FILE, 1, 1, "", "no SOLUTIONS table found")),
+    };
+    let count = lines[table]
+        .trim_start_matches("pub static SOLUTIONS: [&dyn Solution; ")
+        .trim_end_matches("] = [")
+        .parse::<usize>()
+        .map_err(|_| AdventError::parse(LIB_FILE, table + 1, 1, lines[table], "not an int"))?;
+
+    let mut registered = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if Some(i) == module_at {
+            registered.push(module.clone());
+        }
+        if Some(i) == entry_at || (entry_at.is_none() && i == table_end) {
+            registered.push(entry.clone());
+        }
+        if i == table {
+            registered.push(format!("pub static SOLUTIONS: [&dyn Solution; {}] = [", count + 1));
+        } else {
+            registered.push(line.to_string());
+        }
+        if module_at.is_none() && i == last_module {
+            registered.push(module.clone());
+        }
+    }
+    Ok(registered.join("\n") + "\n")
+}
+
+/// Parses the day out of lines such as `pub mod day7;` or `&day7::Day7,`.
+fn registered_day(line: &str, prefix: &str, suffix: &str) -> Option<u32> {
+    if !line.starts_with(prefix) || !line.ends_with(suffix) {
+        return None;
+    }
+    let rest = &line[prefix.len()..];
+    let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    rest[..digits].parse().ok()
+}
+
+/// Generates a new day inside the crate at `root`: writes the module, registers it in `lib.rs`
+/// and creates empty input and example files. Returns the files that were written, existing
+/// files are never overwritten.
+pub fn create(root: &Path, day: u32, title: &str) -> Result<Vec<PathBuf>> {
+    let lib_file = root.join(LIB_FILE);
+    let lib_name = lib_file.to_string_lossy().to_string();
+    let lib_source = fs::read_to_string(&lib_file).map_err(|e| AdventError::io(&lib_name, e))?;
+    let lib_source = register(&lib_source, day).map_err(|e| e.with_filename(&lib_name))?;
+
+    let module_dir = root.join(format!("src/day{}", day));
+    let module_file = module_dir.join("mod.rs");
+    fs::create_dir_all(&module_dir).map_err(|e| AdventError::io(&module_dir.to_string_lossy(), e))?;
+    create_file(&module_file, &module_source(day, title))?;
+    fs::write(&lib_file, lib_source).map_err(|e| AdventError::io(&lib_name, e))?;
+
+    let mut created = vec![module_file, lib_file];
+    for data_file in &[format!("data/day{}-input.txt", day), watch::example_filename(day)] {
+        let data_file = root.join(data_file);
+        if !data_file.exists() {
+            create_file(&data_file, "")?;
+            created.push(data_file);
+        }
+    }
+    Ok(created)
+}
+
+fn create_file(filename: &Path, contents: &str) -> Result<()> {
+    let name = filename.to_string_lossy();
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(filename)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e: io::Error| AdventError::io(&name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    const LIB: &str = "pub mod day1;
+pub mod day3;
+
+pub mod error;
+
+pub static SOLUTIONS: [&dyn Solution; 2] = [
+    &day1::Day1,
+    &day3::Day3,
+];
+";
+
+    #[test]
+    fn register_in_day_order() {
+        let expected = "pub mod day1;
+pub mod day2;
+pub mod day3;
+
+pub mod error;
+
+pub static SOLUTIONS: [&dyn Solution; 3] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+];
+";
+        assert_eq!(expected, register(LIB, 2).unwrap());
+
+        let registered = register(LIB, 13).unwrap();
+        assert!(registered.contains("pub mod day3;\npub mod day13;\n"));
+        assert!(registered.contains("[&dyn Solution; 3]"));
+        assert!(registered.contains("    &day3::Day3,\n    &day13::Day13,\n];"));
+    }
+
+    #[test]
+    fn register_errors() {
+        let e = register(LIB, 3).unwrap_err();
+        assert_eq!("src/lib.rs:2:1: day 3 is already registered: 'pub mod day3;'", e.to_string());
+        assert!(register("pub mod error;\n", 2).is_err());
+    }
+
+    #[test]
+    fn module_source_names_the_day() {
+        let source = module_source(13, "Mine \"Cart\" Madness");
+        assert!(source.contains("pub struct Day13;"));
+        assert!(source.contains("Input::File(\"data/day13-input.txt\")"));
+        assert!(source.contains("\"Mine \\\"Cart\\\" Madness\""));
+        assert!(!source.contains("__"));
+    }
+
+    #[test]
+    fn create_day() {
+        let root = env::temp_dir().join(format!("advent-scaffold-{}", process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("data")).unwrap();
+        fs::write(root.join(LIB_FILE), LIB).unwrap();
+
+        let created = create(&root, 2, "Inventory Management System").unwrap();
+        assert_eq!(4, created.len());
+        assert!(fs::read_to_string(root.join("src/day2/mod.rs")).unwrap().contains("pub struct Day2;"));
+        assert!(fs::read_to_string(root.join(LIB_FILE)).unwrap().contains("&day2::Day2,"));
+        assert!(root.join("data/day2-input.txt").exists());
+        assert!(root.join("data/day2-example.txt").exists());
+
+        // an existing module is left alone
+        fs::write(root.join(LIB_FILE), LIB).unwrap();
+        assert!(create(&root, 2, "Again").is_err());
+        assert_eq!(LIB, fs::read_to_string(root.join(LIB_FILE)).unwrap());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
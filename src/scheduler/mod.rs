@@ -0,0 +1,201 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use {AdventError, Result};
+
+struct Task<K> {
+    duration: u64,
+    dependencies: usize,
+    dependents: Vec<K>,
+}
+
+/// A set of tasks that take time to complete and may have to wait for other tasks to finish.
+pub struct Graph<K> {
+    tasks: HashMap<K, Task<K>>,
+}
+
+/// One task of a schedule, `worker` spends from `start` up to `end` on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot<K> {
+    pub task: K,
+    pub worker: usize,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// When and by whom every task of a graph is worked on, slots are in the order they start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule<K> {
+    slots: Vec<Slot<K>>,
+}
+
+impl<K: Hash + Eq + Clone> Graph<K> {
+    pub fn new() -> Graph<K> {
+        Graph { tasks: HashMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Adds a task, or changes the duration of a task that is already in the graph.
+    pub fn add_task(&mut self, id: K, duration: u64) {
+        self.task(id).duration = duration;
+    }
+
+    /// Makes `after` wait for `before` to finish, tasks that are not in the graph yet are added
+    /// with a duration of zero.
+    pub fn add_dependency(&mut self, before: K, after: K) {
+        self.task(before).dependents.push(after.clone());
+        self.task(after).dependencies += 1;
+    }
+
+    fn task(&mut self, id: K) -> &mut Task<K> {
+        self.tasks.entry(id).or_insert(Task {
+            duration: 0,
+            dependencies: 0,
+            dependents: Vec::new(),
+        })
+    }
+
+    /// Schedules every task on `workers` workers. Whenever a worker is idle it starts the ready
+    /// task that `order` sorts first, idle workers are handed tasks from the lowest numbered up.
+    pub fn schedule<F>(&self, workers: usize, mut order: F) -> Result<Schedule<K>>
+        where F: FnMut(&K, &K) -> Ordering {
+        if workers == 0 {
+            return Err(AdventError::no_answer("at least one worker is required"));
+        }
+
+        let mut waiting: HashMap<&K, usize> = HashMap::new();
+        let mut ready: Vec<&K> = Vec::new();
+        for (id, task) in &self.tasks {
+            if task.dependencies == 0 {
+                ready.push(id);
+            } else {
+                waiting.insert(id, task.dependencies);
+            }
+        }
+
+        let mut busy: Vec<Option<(&K, u64)>> = vec![None; workers];
+        let mut slots = Vec::with_capacity(self.tasks.len());
+        let mut now = 0;
+        loop {
+            // sorted backwards so the task that goes first can be popped off the end
+            ready.sort_by(|a, b| order(b, a));
+            for (worker, slot) in busy.iter_mut().enumerate() {
+                if slot.is_some() {
+                    continue;
+                }
+                let id = match ready.pop() {
+                    None => break,
+                    Some(id) => id,
+                };
+                let end = now + self.tasks[id].duration;
+                *slot = Some((id, end));
+                slots.push(Slot { task: id.clone(), worker, start: now, end });
+            }
+
+            now = match busy.iter().flatten().map(|&(_, end)| end).min() {
+                None => break,
+                Some(end) => end,
+            };
+
+            // every task finishing now frees its worker and possibly some dependents
+            for slot in busy.iter_mut() {
+                match *slot {
+                    Some((id, end)) if end == now => {
+                        *slot = None;
+                        for dependent in &self.tasks[id].dependents {
+                            let remaining = waiting.get_mut(dependent).expect("dependent is waiting");
+                            *remaining -= 1;
+                            if *remaining == 0 {
+                                waiting.remove(dependent);
+                                ready.push(dependent);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if !waiting.is_empty() {
+            return Err(AdventError::no_answer("the tasks contain a dependency cycle"));
+        }
+        Ok(Schedule { slots })
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for Graph<K> {
+    fn default() -> Graph<K> {
+        Graph::new()
+    }
+}
+
+impl<K> Schedule<K> {
+    pub fn slots(&self) -> &[Slot<K>] {
+        &self.slots
+    }
+
+    /// The tasks in the order they were started.
+    pub fn order(&self) -> impl Iterator<Item=&K> {
+        self.slots.iter().map(|slot| &slot.task)
+    }
+
+    /// The time the last task finishes.
+    pub fn end(&self) -> u64 {
+        self.slots.iter().map(|slot| slot.end).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> Graph<&'static str> {
+        //   a -> c
+        //   b -> c -> d
+        let mut graph = Graph::new();
+        graph.add_task("a", 3);
+        graph.add_task("b", 1);
+        graph.add_task("c", 2);
+        graph.add_task("d", 1);
+        graph.add_dependency("a", "c");
+        graph.add_dependency("b", "c");
+        graph.add_dependency("c", "d");
+        graph
+    }
+
+    #[test]
+    fn one_worker() {
+        let schedule = graph().schedule(1, |a, b| a.cmp(b)).unwrap();
+        assert_eq!(vec!["a", "b", "c", "d"], schedule.order().cloned().collect::<Vec<_>>());
+        assert_eq!(7, schedule.end());
+    }
+
+    #[test]
+    fn several_workers() {
+        let schedule = graph().schedule(2, |a, b| b.cmp(a)).unwrap();
+        let slot = |task, worker, start, end| Slot { task, worker, start, end };
+        assert_eq!(&[slot("b", 0, 0, 1), slot("a", 1, 0, 3), slot("c", 0, 3, 5), slot("d", 0, 5, 6)],
+                   schedule.slots());
+        assert_eq!(6, schedule.end());
+    }
+
+    #[test]
+    fn errors() {
+        let mut cycle = graph();
+        cycle.add_dependency("d", "a");
+        let e = cycle.schedule(2, |a, b| a.cmp(b)).unwrap_err();
+        assert_eq!("no answer: the tasks contain a dependency cycle", e.to_string());
+        assert!(graph().schedule(0, |a, b| a.cmp(b)).is_err());
+
+        let empty: Graph<u32> = Graph::new();
+        assert_eq!(0, empty.schedule(3, |a, b| a.cmp(b)).unwrap().end());
+    }
+}
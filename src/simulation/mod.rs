@@ -0,0 +1,187 @@
+use cancel;
+use Result;
+
+/// Something that changes one discrete step at a time.
+pub trait Simulation {
+    /// A copy of everything that describes where the simulation is.
+    type State;
+
+    fn step(&mut self);
+
+    /// Takes back the last step, returning false when the simulation cannot go backwards.
+    fn undo(&mut self) -> bool {
+        false
+    }
+
+    fn snapshot(&self) -> Self::State;
+
+    /// Whether the simulation has come to its natural end, it is never stepped past it.
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+/// Called with the number of steps taken after every step.
+type Observer<'a, S> = Box<dyn FnMut(u64, &S) + 'a>;
+
+/// Runs a simulation, counting the steps and telling observers about each one.
+///
+/// Every step checks the cancellation token so a long run stops when its time is up.
+pub struct Driver<'a, S> {
+    simulation: S,
+    steps: u64,
+    observers: Vec<Observer<'a, S>>,
+}
+
+impl<'a, S: Simulation> Driver<'a, S> {
+    pub fn new(simulation: S) -> Driver<'a, S> {
+        Driver {
+            simulation,
+            steps: 0,
+            observers: Vec::new(),
+        }
+    }
+
+    /// Adds an observer, such as a logger or a metric, called after every step.
+    pub fn observe<F>(mut self, observer: F) -> Driver<'a, S>
+        where F: FnMut(u64, &S) + 'a {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    pub fn simulation(&self) -> &S {
+        &self.simulation
+    }
+
+    pub fn into_simulation(self) -> S {
+        self.simulation
+    }
+
+    /// The number of steps taken, less any that were undone.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn snapshot(&self) -> S::State {
+        self.simulation.snapshot()
+    }
+
+    /// Takes one step, returning false instead when the simulation has finished.
+    pub fn step(&mut self) -> Result<bool> {
+        cancel::check()?;
+        if self.simulation.is_finished() {
+            return Ok(false);
+        }
+        self.simulation.step();
+        self.steps += 1;
+        for observer in &mut self.observers {
+            observer(self.steps, &self.simulation);
+        }
+        Ok(true)
+    }
+
+    /// Steps until `steps` steps have been taken in total or the simulation finishes.
+    pub fn step_to(&mut self, steps: u64) -> Result<u64> {
+        while self.steps < steps && self.step()? {}
+        Ok(self.steps)
+    }
+
+    /// Steps until the simulation finishes, returning the number of steps taken in total.
+    pub fn run(&mut self) -> Result<u64> {
+        while self.step()? {}
+        Ok(self.steps)
+    }
+
+    /// Steps until `done` holds after a step or the simulation finishes, returning the number
+    /// of steps taken in total.
+    pub fn run_until<P>(&mut self, mut done: P) -> Result<u64>
+        where P: FnMut(&S) -> bool {
+        while self.step()? {
+            if done(&self.simulation) {
+                break;
+            }
+        }
+        Ok(self.steps)
+    }
+
+    /// Takes back the last step, returning false when there is none or the simulation cannot.
+    pub fn undo(&mut self) -> bool {
+        if self.steps == 0 || !self.simulation.undo() {
+            return false;
+        }
+        self.steps -= 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use AdventError;
+
+    /// Counts up to a limit.
+    struct Counter {
+        value: u32,
+        limit: u32,
+    }
+
+    impl Simulation for Counter {
+        type State = u32;
+
+        fn step(&mut self) {
+            self.value += 1;
+        }
+
+        fn undo(&mut self) -> bool {
+            self.value -= 1;
+            true
+        }
+
+        fn snapshot(&self) -> u32 {
+            self.value
+        }
+
+        fn is_finished(&self) -> bool {
+            self.value == self.limit
+        }
+    }
+
+    #[test]
+    fn drive() {
+        let mut driver = Driver::new(Counter { value: 0, limit: 10 });
+        assert_eq!(3, driver.step_to(3).unwrap());
+        assert_eq!(6, driver.run_until(|c| c.value % 6 == 0).unwrap());
+        assert!(driver.undo());
+        assert_eq!(5, driver.snapshot());
+        assert_eq!(10, driver.run().unwrap());
+        assert!(!driver.step().unwrap());
+        assert_eq!(10, driver.step_to(20).unwrap());
+        assert_eq!(10, driver.into_simulation().value);
+    }
+
+    #[test]
+    fn observers() {
+        let seen = RefCell::new(Vec::new());
+        let mut total = 0;
+        {
+            let mut driver = Driver::new(Counter { value: 0, limit: 3 })
+                .observe(|steps, c| seen.borrow_mut().push((steps, c.value)))
+                .observe(|_, c| total += c.value);
+            driver.run().unwrap();
+        }
+        assert_eq!(vec![(1, 1), (2, 2), (3, 3)], seen.into_inner());
+        assert_eq!(6, total);
+    }
+
+    #[test]
+    fn cancelled() {
+        let token = cancel::Token::new();
+        token.cancel();
+        let e = cancel::with_token(token, || Driver::new(Counter { value: 0, limit: 3 }).run()).unwrap_err();
+        match e {
+            AdventError::Cancelled => {}
+            e => panic!("expected cancelled, got {}", e),
+        }
+    }
+}
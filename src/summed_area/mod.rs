@@ -0,0 +1,161 @@
+use std::ops::{Add, Sub};
+
+use geometry::{BoundingBox, Point, Vector};
+use grid::Grid;
+
+/// A number a summed-area table can hold.
+pub trait Value: Copy + Default + Add<Output=Self> + Sub<Output=Self> {
+    fn to_f64(self) -> f64;
+}
+
+impl Value for i32 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Value for i64 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Value for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// Answers sums over any rectangle of a grid in constant time.
+///
+/// Each entry holds the total of every cell above and to the left of it, inclusive, with an
+/// extra row and column of zeros in front so rectangles touching the edge need no special case.
+/// The squares of the cells are summed too so variances are just as cheap, as `f64` so only the
+/// sums have to fit in `T`.
+#[derive(Debug, Clone)]
+pub struct SummedAreaTable<T> {
+    sums: Grid<T>,
+    squares: Grid<f64>,
+}
+
+impl<T: Value> SummedAreaTable<T> {
+    /// Builds a table of the values of `f` at each location, positions start at `(0, 0)` like
+    /// `Grid::from_fn`.
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> SummedAreaTable<T>
+        where F: FnMut(Point) -> T {
+        let (left, up, diagonal) = (Vector::new(-1, 0), Vector::new(0, -1), Vector::new(-1, -1));
+        let mut sums = Grid::new(width + 1, height + 1, T::default()).with_origin(Point::new(-1, -1));
+        let mut squares = Grid::new(width + 1, height + 1, 0.0).with_origin(Point::new(-1, -1));
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let p = Point::new(x, y);
+                let value = f(p);
+                sums[p] = value + sums[p + left] + sums[p + up] - sums[p + diagonal];
+                let square = value.to_f64() * value.to_f64();
+                squares[p] = square + squares[p + left] + squares[p + up] - squares[p + diagonal];
+            }
+        }
+        SummedAreaTable { sums, squares }
+    }
+
+    /// Builds a table of the cells of `grid`, keeping its coordinates.
+    pub fn from_grid(grid: &Grid<T>) -> SummedAreaTable<T> {
+        let min = grid.min();
+        SummedAreaTable::from_fn(grid.width(), grid.height(), |p| grid[min + Vector::new(p.x, p.y)])
+            .with_origin(min)
+    }
+
+    /// Moves the table so its top left cell is at `origin`.
+    pub fn with_origin(self, origin: Point) -> SummedAreaTable<T> {
+        let border = origin - Vector::new(1, 1);
+        SummedAreaTable {
+            sums: self.sums.with_origin(border),
+            squares: self.squares.with_origin(border),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.sums.width() - 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.sums.height() - 1
+    }
+
+    /// The total of every cell in `area`, panicking when the area is not inside the table.
+    pub fn sum(&self, area: &BoundingBox) -> T {
+        total(&self.sums, area)
+    }
+
+    /// The average of the cells in `area`.
+    pub fn mean(&self, area: &BoundingBox) -> f64 {
+        self.sum(area).to_f64() / area.area() as f64
+    }
+
+    /// The population variance of the cells in `area`.
+    pub fn variance(&self, area: &BoundingBox) -> f64 {
+        let mean = self.mean(area);
+        let squares = total(&self.squares, area) / area.area() as f64;
+        // rounding can take a flat area just below zero
+        (squares - mean * mean).max(0.0)
+    }
+}
+
+fn total<T: Value>(table: &Grid<T>, area: &BoundingBox) -> T {
+    let (left, top) = (area.min.x - 1, area.min.y - 1);
+    let (right, bottom) = (area.max.x, area.max.y);
+    table[Point::new(right, bottom)] - table[Point::new(right, top)] - table[Point::new(left, bottom)]
+        + table[Point::new(left, top)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(x1: i32, y1: i32, x2: i32, y2: i32) -> BoundingBox {
+        BoundingBox::new(Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    #[test]
+    fn sums_match_brute_force() {
+        let grid = Grid::from_fn(7, 5, |p| (p.x * 31 + p.y * 17) % 11 - 5).with_origin(Point::new(-3, 2));
+        let table = SummedAreaTable::from_grid(&grid);
+        assert_eq!((7, 5), (table.width(), table.height()));
+
+        let bounds = grid.bounds().unwrap();
+        for start in bounds.points() {
+            for end in BoundingBox::new(start, bounds.max).points() {
+                let rect = BoundingBox::new(start, end);
+                let expected: i32 = rect.points().map(|p| grid[p]).sum();
+                assert_eq!(expected, table.sum(&rect));
+            }
+        }
+    }
+
+    #[test]
+    fn mean_and_variance() {
+        // 1 2
+        // 3 4
+        let table = SummedAreaTable::from_fn(2, 2, |p| (p.y * 2 + p.x + 1) as f64);
+        assert_eq!(10.0, table.sum(&area(0, 0, 1, 1)));
+        assert_eq!(2.5, table.mean(&area(0, 0, 1, 1)));
+        assert_eq!(1.25, table.variance(&area(0, 0, 1, 1)));
+        assert_eq!(0.0, table.variance(&area(1, 1, 1, 1)));
+
+        let large = SummedAreaTable::from_fn(2, 1, |_| 1_500_000_000i64);
+        assert_eq!(3_000_000_000, large.sum(&area(0, 0, 1, 0)));
+
+        // the squares of these cells do not fit in an i32, only their sums have to
+        let wide = SummedAreaTable::from_fn(3, 3, |p| 100_000 * (p.x - 1));
+        assert_eq!(0, wide.sum(&area(0, 0, 2, 2)));
+        assert_eq!(100_000.0, wide.mean(&area(2, 0, 2, 2)));
+        assert_eq!(0.0, wide.variance(&area(2, 0, 2, 2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the grid")]
+    fn outside_the_table() {
+        let table = SummedAreaTable::from_fn(2, 2, |_| 1);
+        table.sum(&area(0, 0, 2, 1));
+    }
+}
@@ -0,0 +1,100 @@
+use std::fs;
+use std::time::SystemTime;
+
+/// How often `advent watch` checks its files for changes, in milliseconds.
+pub const DEFAULT_INTERVAL: u64 = 500;
+
+/// Returns the example input kept next to a day's puzzle input.
+pub fn example_filename(day: u32) -> String {
+    format!("data/day{}-example.txt", day)
+}
+
+/// Polls the modification times of a set of files.
+///
+/// A file that does not exist yet is still watched, creating it counts as a change.
+pub struct Watcher {
+    files: Vec<(String, Option<SystemTime>)>,
+}
+
+impl Watcher {
+    pub fn new(filenames: &[String]) -> Watcher {
+        let files = filenames.iter().map(|f| (f.clone(), modified(f))).collect();
+        Watcher { files }
+    }
+
+    /// Returns the files that were created, changed or removed since the last poll.
+    pub fn changed(&mut self) -> Vec<String> {
+        let mut changed = Vec::new();
+        for &mut (ref filename, ref mut last) in self.files.iter_mut() {
+            let current = modified(filename);
+            if current != *last {
+                *last = current;
+                changed.push(filename.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn modified(filename: &str) -> Option<SystemTime> {
+    fs::metadata(filename).and_then(|m| m.modified()).ok()
+}
+
+/// Compares two answers line by line, returning the removed lines prefixed with `-` and the
+/// added lines prefixed with `+`. Identical answers produce no lines.
+pub fn diff(previous: &str, current: &str) -> Vec<String> {
+    let previous: Vec<&str> = previous.lines().collect();
+    let current: Vec<&str> = current.lines().collect();
+    let mut lines = Vec::new();
+    for i in 0..previous.len().max(current.len()) {
+        let (before, after) = (previous.get(i), current.get(i));
+        if before == after {
+            continue;
+        }
+        if let Some(line) = before {
+            lines.push(format!("- {}", line));
+        }
+        if let Some(line) = after {
+            lines.push(format!("+ {}", line));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+    use std::time::Duration;
+
+    #[test]
+    fn diff_answers() {
+        assert!(diff("592", "592").is_empty());
+        assert_eq!(vec!["- 592", "+ 593"], diff("592", "593"));
+        assert_eq!(vec!["- #..#", "+ #.##", "+ ...."], diff("####\n#..#", "####\n#.##\n...."));
+    }
+
+    #[test]
+    fn watcher_reports_changes() {
+        let filename = env::temp_dir().join(format!("advent-watch-{}.txt", std::process::id()));
+        let filename = filename.to_str().unwrap().to_string();
+        let _ = fs::remove_file(&filename);
+
+        let files = vec![filename.clone()];
+        let mut watcher = Watcher::new(&files);
+        assert!(watcher.changed().is_empty());
+
+        fs::write(&filename, "1").unwrap();
+        assert_eq!(vec![filename.clone()], watcher.changed());
+        assert!(watcher.changed().is_empty());
+
+        // move the modification time explicitly, file systems may only store whole seconds
+        let file = File::options().write(true).open(&filename).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        assert_eq!(vec![filename.clone()], watcher.changed());
+
+        fs::remove_file(&filename).unwrap();
+        assert_eq!(vec![filename.clone()], watcher.changed());
+    }
+}